@@ -0,0 +1,407 @@
+use crate::value::{kwargs, FieldError, RawValue};
+use py_apify_core::problem::{
+    generate_request_id, is_valid_request_id, problem, PROBLEM_MEDIA_TYPE, REQUEST_ID_HEADER,
+};
+use py_apify_core::py_arg::{PyArg, PyEnum, PyPrimitiveDataType, PyStructKind};
+use py_apify_core::py_module::{PyException, PY_APIFY_MODULE_NAME};
+use py_apify_core::python_file::{index_document, PythonFile};
+use py_apify_core::result::py_result_to_json;
+use pyo3::types::{PyDict, PyList, PyModule};
+use pyo3::{Py, PyAny, PyErr, PyResult, Python};
+use rocket::data::ToByteUnit;
+use rocket::http::{ContentType, Method, Status};
+use rocket::response::{self, Responder};
+use rocket::route::{Handler, Outcome};
+use rocket::{Data, Request, Response, Route};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Cursor;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub enum ServeError {
+    /// No route matches the method and path of the request
+    NotFound(String),
+    InvalidArguments(Vec<FieldError>),
+    InvalidBody(String),
+    HookFunctionFailure(String),
+    InvalidReturnValue(String),
+    PythonException {
+        status: u16,
+        exception: String,
+        detail: String,
+    },
+}
+
+impl ServeError {
+    /// Maps an exception raised by `call` like the `apify!` routes do, see
+    /// `PyException::from_py_err`.
+    fn from_py_err(py: Python, e: PyErr) -> Self {
+        let py_apify_module = py.import(PY_APIFY_MODULE_NAME).ok();
+
+        match PyException::from_py_err(py, py_apify_module, &e) {
+            PyException::Rejected {
+                status,
+                exception,
+                detail,
+            } => ServeError::PythonException {
+                status,
+                exception,
+                detail,
+            },
+            PyException::Failure(e) => ServeError::HookFunctionFailure(e),
+        }
+    }
+
+    fn status(&self) -> Status {
+        match self {
+            ServeError::NotFound(_) => Status::NotFound,
+            ServeError::InvalidArguments(_) => Status::UnprocessableEntity,
+            ServeError::InvalidBody(_) => Status::BadRequest,
+            ServeError::HookFunctionFailure(_) | ServeError::InvalidReturnValue(_) => {
+                Status::InternalServerError
+            }
+            ServeError::PythonException { status, .. } => Status::from_code(*status)
+                .filter(|status| status.code >= 400)
+                .unwrap_or(Status::InternalServerError),
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            ServeError::NotFound(_) => "not-found",
+            ServeError::InvalidArguments(_) => "invalid-fields",
+            ServeError::InvalidBody(_) => "invalid-arguments",
+            ServeError::HookFunctionFailure(_) => "hook-function-failure",
+            ServeError::InvalidReturnValue(_) => "invalid-return-value",
+            ServeError::PythonException { .. } => "python-exception",
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            ServeError::NotFound(_) => "Not found",
+            ServeError::InvalidArguments(_) | ServeError::InvalidBody(_) => "Invalid arguments",
+            ServeError::HookFunctionFailure(_) => "The `call` function raised an exception",
+            ServeError::InvalidReturnValue(_) => {
+                "The `call` function returned a value that can't be converted to JSON"
+            }
+            ServeError::PythonException { .. } => "The `call` function rejected the request",
+        }
+    }
+
+    /// Problem document answering the error, the same as the `apify!` routes.
+    fn problem(&self, request_id: &str) -> Value {
+        let detail = match self {
+            ServeError::PythonException { detail, .. } => detail.clone(),
+            _ => self.to_string(),
+        };
+
+        let mut problem = problem(
+            self.kind(),
+            self.title(),
+            self.status().code,
+            &detail,
+            request_id,
+        );
+
+        match self {
+            ServeError::PythonException { exception, .. } => {
+                problem["exception"] = Value::from(exception.as_str());
+            }
+            ServeError::InvalidArguments(errors) => {
+                problem["fields"] = errors
+                    .iter()
+                    .map(|error| {
+                        json!({
+                            "name": error.name,
+                            "expected": error.expected,
+                            "reason": error.reason,
+                        })
+                    })
+                    .collect();
+            }
+            _ => {}
+        }
+
+        problem
+    }
+}
+
+impl fmt::Display for ServeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ServeError::NotFound(route) => write!(f, "No route matches {}", route),
+            ServeError::InvalidArguments(errors) => write!(
+                f,
+                "Invalid arguments: {}",
+                errors
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            ServeError::InvalidBody(e) => write!(f, "Invalid body: {}", e),
+            ServeError::HookFunctionFailure(e) => {
+                write!(f, "The hook function returned an error : {}", e)
+            }
+            ServeError::InvalidReturnValue(e) => write!(
+                f,
+                "The hook function returned a value that can't be converted to JSON : {}",
+                e
+            ),
+            ServeError::PythonException {
+                exception, detail, ..
+            } => write!(f, "{}: {}", exception, detail),
+        }
+    }
+}
+
+/// Id of a request, cached for the rest of the request.
+struct RequestId(String);
+
+/// Id of a request: the `X-Request-Id` header when the client sets a valid
+/// one, a new id otherwise.
+fn request_id<'r>(req: &'r Request<'_>) -> &'r str {
+    &req.local_cache(|| {
+        RequestId(
+            req.headers()
+                .get_one(REQUEST_ID_HEADER)
+                .filter(|id| is_valid_request_id(id))
+                .map(str::to_string)
+                .unwrap_or_else(generate_request_id),
+        )
+    })
+    .0
+}
+
+/// Errors are answered with the same RFC 7807 `application/problem+json`
+/// document as in the `apify!` routes.
+impl<'r> Responder<'r, 'static> for ServeError {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let request_id = request_id(req);
+        let problem = self.problem(request_id).to_string();
+        let (top, sub) = PROBLEM_MEDIA_TYPE;
+
+        Response::build()
+            .status(self.status())
+            .header(ContentType::new(top, sub))
+            .raw_header(REQUEST_ID_HEADER, request_id.to_string())
+            .sized_body(problem.len(), Cursor::new(problem))
+            .ok()
+    }
+}
+
+/// Answers requests matching no route with a problem, the status of
+/// requests rejected by a route is set by their handler.
+#[rocket::catch(404)]
+pub fn not_found(req: &Request) -> ServeError {
+    ServeError::NotFound(format!("{} {}", req.method(), req.uri().path()))
+}
+
+/// Converts a value decoded from JSON to what `call` receives in the `apify!`
+/// routes: `Enum` parameters get the members of their class and dataclass
+/// parameters an instance of the class.
+fn to_python<'py>(
+    py: Python<'py>,
+    module: &'py PyModule,
+    data_type: &PyPrimitiveDataType,
+    value: &'py PyAny,
+) -> PyResult<&'py PyAny> {
+    if value.is_none() {
+        return Ok(value);
+    }
+
+    match data_type {
+        PyPrimitiveDataType::Enum(PyEnum {
+            class_name: Some(class_name),
+            ..
+        }) => module.getattr(class_name)?.call1((value,)),
+        PyPrimitiveDataType::List(item_data_type) => {
+            let items = value
+                .iter()?
+                .map(|item| to_python(py, module, item_data_type, item?))
+                .collect::<PyResult<Vec<&PyAny>>>()?;
+
+            Ok(PyList::new(py, items).as_ref())
+        }
+        PyPrimitiveDataType::Struct(py_struct) => {
+            let fields: &PyDict = value.downcast()?;
+
+            for field in &py_struct.fields {
+                if let Some(field_value) = fields.get_item(&field.name) {
+                    fields.set_item(
+                        &field.name,
+                        to_python(py, module, &field.data_type, field_value)?,
+                    )?;
+                }
+            }
+
+            match py_struct.kind {
+                PyStructKind::TypedDict => Ok(fields.as_ref()),
+                PyStructKind::Dataclass => module
+                    .getattr(&py_struct.class_name)?
+                    .call((), Some(fields)),
+            }
+        }
+        _ => Ok(value),
+    }
+}
+
+/// Serves the index document listing the routes and their documentation.
+#[derive(Clone)]
+pub struct IndexHandler {
+    document: Arc<String>,
+}
+
+impl IndexHandler {
+    /// Builds the `GET /` route.
+    pub fn route(python_files: &[PythonFile]) -> Route {
+        let handler = IndexHandler {
+            document: Arc::new(index_document(python_files).to_string()),
+        };
+
+        Route::new(Method::Get, "/", handler)
+    }
+}
+
+#[rocket::async_trait]
+impl Handler for IndexHandler {
+    async fn handle<'r>(&self, req: &'r Request<'_>, _data: Data<'r>) -> Outcome<'r> {
+        Outcome::from(req, (ContentType::JSON, self.document.to_string()))
+    }
+}
+
+/// Serves the `call` function of a Python module loaded at runtime.
+#[derive(Clone)]
+pub struct PyApifyHandler {
+    file_name: Arc<String>,
+    args: Arc<Vec<PyArg>>,
+    module: Arc<Py<PyModule>>,
+}
+
+impl PyApifyHandler {
+    /// Builds the `GET /<stem>` and `POST /<stem>` routes of a Python file.
+    pub fn routes(python_file: &PythonFile, module: Py<PyModule>) -> Vec<Route> {
+        let handler = PyApifyHandler {
+            file_name: Arc::new(python_file.file_name.clone()),
+            args: Arc::new(python_file.main_func_args.clone()),
+            module: Arc::new(module),
+        };
+
+        let path = format!("/{}", python_file.file_stem);
+
+        vec![
+            Route::new(Method::Get, &path, handler.clone()),
+            Route::new(Method::Post, &path, handler),
+        ]
+    }
+
+    fn query_fields(req: &Request<'_>) -> HashMap<String, RawValue> {
+        let mut fields: HashMap<String, RawValue> = HashMap::new();
+
+        for field in req.query_fields() {
+            let value = field.value.to_string();
+
+            match fields
+                .entry(field.name.key_lossy().to_string())
+                .or_insert_with(|| RawValue::Query(vec![]))
+            {
+                RawValue::Query(values) => values.push(value),
+                RawValue::Json(_) => unreachable!(),
+            }
+        }
+
+        fields
+    }
+
+    async fn body_fields(
+        req: &Request<'_>,
+        data: Data<'_>,
+    ) -> Result<HashMap<String, RawValue>, ServeError> {
+        let limit = req.limits().get("json").unwrap_or_else(|| 1.mebibytes());
+
+        let body = data
+            .open(limit)
+            .into_string()
+            .await
+            .map_err(|e| ServeError::InvalidBody(e.to_string()))?;
+
+        if !body.is_complete() {
+            return Err(ServeError::InvalidBody("payload too large".into()));
+        }
+
+        match serde_json::from_str(&body.into_inner()) {
+            Ok(Value::Object(object)) => Ok(object
+                .into_iter()
+                .map(|(name, value)| (name, RawValue::Json(value)))
+                .collect()),
+            Ok(_) => Err(ServeError::InvalidBody("expected a JSON object".into())),
+            Err(e) => Err(ServeError::InvalidBody(e.to_string())),
+        }
+    }
+
+    fn call(&self, py: Python, kwargs: Map<String, Value>) -> Result<String, ServeError> {
+        let result = self
+            .call_py(py, kwargs)
+            .map_err(|e| ServeError::from_py_err(py, e))?;
+
+        py_result_to_json(result)
+            .map_err(|e| ServeError::InvalidReturnValue(format!("{}: {}", self.file_name, e)))
+    }
+
+    fn call_py<'py>(&self, py: Python<'py>, kwargs: Map<String, Value>) -> PyResult<&'py PyAny> {
+        let kwargs: &PyDict = py
+            .import("json")?
+            .call_method1("loads", (Value::Object(kwargs).to_string(),))?
+            .downcast()?;
+        let module = self.module.clone_ref(py).into_ref(py);
+
+        for py_arg in self.args.iter() {
+            if let Some(value) = kwargs.get_item(&py_arg.name) {
+                kwargs.set_item(
+                    &py_arg.name,
+                    to_python(py, module, &py_arg.data_type, value)?,
+                )?;
+            }
+        }
+
+        log::debug!("calling `call` from {} with {}", self.file_name, kwargs);
+
+        module.getattr("call")?.call((), Some(kwargs))
+    }
+
+    async fn serve(&self, req: &Request<'_>, data: Data<'_>) -> Result<String, ServeError> {
+        let fields = match req.method() {
+            Method::Post => Self::body_fields(req, data).await?,
+            _ => Self::query_fields(req),
+        };
+
+        let kwargs = kwargs(&self.args, fields).map_err(ServeError::InvalidArguments)?;
+        let handler = self.clone();
+
+        rocket::tokio::task::spawn_blocking(move || Python::with_gil(|py| handler.call(py, kwargs)))
+            .await
+            .map_err(|e| ServeError::HookFunctionFailure(e.to_string()))?
+    }
+}
+
+#[rocket::async_trait]
+impl Handler for PyApifyHandler {
+    async fn handle<'r>(&self, req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        match self.serve(req, data).await {
+            Ok(result) => Outcome::Success(
+                Response::build()
+                    .header(ContentType::JSON)
+                    .raw_header(REQUEST_ID_HEADER, request_id(req).to_string())
+                    .sized_body(result.len(), Cursor::new(result))
+                    .finalize(),
+            ),
+            Err(e) => {
+                log::error!("[{}] {} failed: {:?}", request_id(req), self.file_name, e);
+                Outcome::from(req, e)
+            }
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//! `py-apify serve model_a.py model_b.py`
+//!
+//! Serves the `call` function of each Python file without compiling any Rust:
+//! signatures are parsed at startup with the same logic as the `apify!` macro
+//! and the routes are validated at runtime. The routes and the docstrings of
+//! their `call` function are listed on `/`.
+
+mod handler;
+mod value;
+
+use env_logger::Env;
+use handler::{IndexHandler, PyApifyHandler};
+use py_apify_core::py_module::define_py_apify_module;
+use py_apify_core::python_file::get_py_files;
+use pyo3::types::PyModule;
+use pyo3::Python;
+use std::path::Path;
+use std::process::exit;
+
+const USAGE: &str = "usage: py-apify serve <file.py>...";
+
+fn build_rocket(files: Vec<String>) -> Result<rocket::Rocket<rocket::Build>, String> {
+    let py_files = get_py_files(files, Path::new("."), true).map_err(|e| e.to_string())?;
+
+    if let Some(py_file) = py_files.iter().find(|py_file| py_file.has_uploads()) {
+        return Err(format!(
+            "{}: `bytes` and `py_apify.UploadFile` parameters are not supported by `py-apify serve`, use the `apify!` macro to serve uploaded files",
+            py_file.file_name
+        ));
+    }
+
+    pyo3::prepare_freethreaded_python();
+
+    Python::with_gil(|py| {
+        let py_apify_module = define_py_apify_module(py).map_err(|e| e.to_string())?;
+
+        py_files
+            .iter()
+            .try_fold(rocket::build(), |rocket, py_file| {
+                let py_code = std::fs::read_to_string(&py_file.path)
+                    .map_err(|e| format!("{}: {}", py_file.file_name, e))?;
+
+                py_apify_module
+                    .call_method1("_register_file", (&py_file.file_name, &py_file.file_stem))
+                    .map_err(|e| e.to_string())?;
+
+                let module =
+                    PyModule::from_code(py, &py_code, &py_file.file_name, &py_file.module_name())
+                        .map_err(|e| format!("{}: {}", py_file.file_name, e))?;
+
+                log::info!("serving {} on /{}", py_file.file_name, py_file.file_stem);
+
+                Ok(rocket.mount("/", PyApifyHandler::routes(py_file, module.into())))
+            })
+            .map(|rocket| {
+                rocket
+                    .mount("/", vec![IndexHandler::route(&py_files)])
+                    .register("/", rocket::catchers![handler::not_found])
+            })
+    })
+}
+
+#[rocket::main]
+async fn main() {
+    env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
+
+    // collected before any `.await`, `std::env::Args` isn't `Send`
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let files: Vec<String> = match args.split_first() {
+        Some((command, files)) if command == "serve" => files.to_vec(),
+        _ => {
+            eprintln!("{}", USAGE);
+            exit(2);
+        }
+    };
+
+    if files.is_empty() {
+        eprintln!("{}", USAGE);
+        exit(2);
+    }
+
+    let rocket = match build_rocket(files) {
+        Ok(rocket) => rocket,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            exit(1);
+        }
+    };
+
+    if let Err(e) = rocket.launch().await {
+        eprintln!("error: {}", e);
+        exit(1);
+    }
+}
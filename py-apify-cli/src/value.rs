@@ -0,0 +1,417 @@
+use py_apify_core::py_arg::{
+    PyArg, PyConstraint, PyDefault, PyEnum, PyPrimitiveDataType, PyStruct,
+};
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+use std::fmt;
+
+/// A request field before validation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RawValue {
+    /// Every value given for a query parameter
+    Query(Vec<String>),
+    /// A property of the JSON body
+    Json(Value),
+}
+
+/// A field rejected by the validation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldError {
+    pub name: String,
+    /// Python type of the parameter, `None` for unknown parameters
+    pub expected: Option<String>,
+    pub reason: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.name.as_str(), &self.expected) {
+            ("", _) => write!(f, "{}", self.reason),
+            (name, Some(expected)) => write!(f, "`{}` ({}): {}", name, expected, self.reason),
+            (name, None) => write!(f, "`{}`: {}", name, self.reason),
+        }
+    }
+}
+
+/// Python type of a parameter, as shown in validation errors.
+fn python_type(py_arg: &PyArg) -> String {
+    match py_arg.optional {
+        true => format!("{} | None", py_arg.data_type.python_name()),
+        false => py_arg.data_type.python_name(),
+    }
+}
+
+fn check_enum_value(py_enum: &PyEnum, value: &str) -> Result<(), String> {
+    if py_enum.values.iter().any(|v| v == value) {
+        return Ok(());
+    }
+
+    let values: Vec<String> = py_enum.values.iter().map(|v| format!("{:?}", v)).collect();
+    Err(format!("expected one of {}", values.join(", ")))
+}
+
+fn parse_scalar(data_type: &PyPrimitiveDataType, raw: &str) -> Result<Value, String> {
+    match data_type {
+        PyPrimitiveDataType::Str => Ok(Value::String(raw.to_string())),
+        PyPrimitiveDataType::Int => raw
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|e| format!("invalid integer: {}", e)),
+        PyPrimitiveDataType::Float => raw
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| "invalid float".to_string()),
+        PyPrimitiveDataType::Bool => match raw.to_lowercase().as_ref() {
+            "true" | "on" | "yes" | "1" => Ok(Value::Bool(true)),
+            "false" | "off" | "no" | "0" => Ok(Value::Bool(false)),
+            _ => Err("invalid boolean".into()),
+        },
+        PyPrimitiveDataType::List(_) => Err("nested lists can't be sent in a query string".into()),
+        PyPrimitiveDataType::Bytes | PyPrimitiveDataType::UploadFile => {
+            Err("files can't be sent in a query string".into())
+        }
+        PyPrimitiveDataType::Enum(py_enum) => {
+            check_enum_value(py_enum, raw).map(|_| Value::String(raw.to_string()))
+        }
+        PyPrimitiveDataType::Struct(py_struct) => Err(format!(
+            "a {} can't be sent in a query string, use a JSON body",
+            py_struct.class_name
+        )),
+    }
+}
+
+/// Parses the values of a query parameter.
+pub fn parse_query_value(
+    data_type: &PyPrimitiveDataType,
+    raw_values: &[String],
+) -> Result<Value, String> {
+    match (data_type, raw_values) {
+        (PyPrimitiveDataType::List(item_data_type), _) => raw_values
+            .iter()
+            .map(|raw| parse_scalar(item_data_type, raw))
+            .collect::<Result<Vec<Value>, String>>()
+            .map(Value::Array),
+        (_, [raw]) => parse_scalar(data_type, raw),
+        _ => Err("expected a single value".into()),
+    }
+}
+
+/// Validates the fields of a `TypedDict` or dataclass like the parameters of
+/// `call`, missing fields included.
+fn check_json_object(py_struct: &PyStruct, object: Map<String, Value>) -> Result<Value, String> {
+    let fields = object
+        .into_iter()
+        .map(|(name, value)| (name, RawValue::Json(value)))
+        .collect();
+
+    kwargs(&py_struct.fields, fields)
+        .map(Value::Object)
+        .map_err(|errors| {
+            errors
+                .iter()
+                .map(|error| format!("field `{}`: {}", error.name, error.reason))
+                .collect::<Vec<String>>()
+                .join(", ")
+        })
+}
+
+/// Checks a JSON value against the type of a parameter. The missing fields of
+/// the objects are filled like missing parameters.
+pub fn check_json_value(data_type: &PyPrimitiveDataType, value: Value) -> Result<Value, String> {
+    match (data_type, value) {
+        (PyPrimitiveDataType::Str, value @ Value::String(_)) => Ok(value),
+        (PyPrimitiveDataType::Bool, value @ Value::Bool(_)) => Ok(value),
+        (PyPrimitiveDataType::Float, value @ Value::Number(_)) => Ok(value),
+        (PyPrimitiveDataType::Int, Value::Number(number)) if number.is_i64() => {
+            Ok(Value::Number(number))
+        }
+        (PyPrimitiveDataType::Int, Value::Number(number)) if number.is_u64() => {
+            Err("number too large to fit in a 64 bits signed integer".into())
+        }
+        (PyPrimitiveDataType::Enum(py_enum), Value::String(value)) => {
+            check_enum_value(py_enum, &value).map(|_| Value::String(value))
+        }
+        (PyPrimitiveDataType::List(item_data_type), Value::Array(items)) => items
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| {
+                check_json_value(item_data_type, item).map_err(|e| format!("item {}: {}", i, e))
+            })
+            .collect::<Result<Vec<Value>, String>>()
+            .map(Value::Array),
+        (PyPrimitiveDataType::Struct(py_struct), Value::Object(object)) => {
+            check_json_object(py_struct, object)
+        }
+        _ => Err(format!("expected {}", data_type.python_name())),
+    }
+}
+
+/// Checks a valid value against the `Annotated` constraints of its parameter.
+fn check_constraint(constraint: &PyConstraint, value: &Value) -> Result<(), String> {
+    let length = match value {
+        Value::String(value) => Some(value.chars().count() as u64),
+        Value::Array(items) => Some(items.len() as u64),
+        _ => None,
+    };
+
+    match (constraint, value.as_f64(), length) {
+        (PyConstraint::Range { min, max }, Some(number), _)
+            if min.is_some_and(|min| number < min) || max.is_some_and(|max| number > max) =>
+        {
+            Err(match (min, max) {
+                (Some(min), Some(max)) => format!("value must be between {} and {}", min, max),
+                (Some(min), None) => format!("value must be at least {}", min),
+                (None, max) => format!("value must be at most {}", max.unwrap_or_default()),
+            })
+        }
+        (PyConstraint::MinLen(min), _, Some(length)) if length < *min => {
+            Err(format!("length must be at least {}", min))
+        }
+        (PyConstraint::MaxLen(max), _, Some(length)) if length > *max => {
+            Err(format!("length must be at most {}", max))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn validate(py_arg: &PyArg, raw_value: RawValue) -> Result<Value, String> {
+    let value = match raw_value {
+        RawValue::Query(raw_values) => parse_query_value(&py_arg.data_type, &raw_values)?,
+        RawValue::Json(Value::Null) if py_arg.optional => return Ok(Value::Null),
+        RawValue::Json(value) => check_json_value(&py_arg.data_type, value)?,
+    };
+
+    py_arg
+        .constraints
+        .iter()
+        .try_for_each(|constraint| check_constraint(constraint, &value))?;
+
+    Ok(value)
+}
+
+/// Validates the request fields against the parameters of `call` and builds
+/// its kwargs. Missing parameters are handled like in the `apify!` routes:
+/// literal defaults are applied, optional parameters are set to `None` and
+/// parameters with a non literal default are left to Python.
+pub fn kwargs(
+    args: &[PyArg],
+    mut fields: HashMap<String, RawValue>,
+) -> Result<Map<String, Value>, Vec<FieldError>> {
+    let mut kwargs = Map::new();
+    let mut errors = vec![];
+
+    for py_arg in args {
+        let value = match fields.remove(&py_arg.name) {
+            Some(raw_value) => validate(py_arg, raw_value),
+            None => match &py_arg.default {
+                Some(PyDefault::Expression) => continue,
+                Some(default) => Ok(default.to_json().unwrap_or(Value::Null)),
+                None if py_arg.optional => Ok(Value::Null),
+                None => Err("missing".to_string()),
+            },
+        };
+
+        match value {
+            Ok(value) => {
+                kwargs.insert(py_arg.name.clone(), value);
+            }
+            Err(reason) => errors.push(FieldError {
+                name: py_arg.name.clone(),
+                expected: Some(python_type(py_arg)),
+                reason,
+            }),
+        }
+    }
+
+    let mut unknown_fields: Vec<String> = fields.into_keys().collect();
+    unknown_fields.sort();
+
+    errors.extend(unknown_fields.into_iter().map(|name| FieldError {
+        name,
+        expected: None,
+        reason: "unexpected".into(),
+    }));
+
+    if errors.is_empty() {
+        Ok(kwargs)
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use py_apify_core::py_arg::PyStructKind;
+    use serde_json::json;
+
+    fn py_arg(
+        name: &str,
+        data_type: PyPrimitiveDataType,
+        optional: bool,
+        default: Option<PyDefault>,
+    ) -> PyArg {
+        PyArg {
+            name: name.into(),
+            data_type,
+            optional,
+            default,
+            constraints: vec![],
+            doc: None,
+        }
+    }
+
+    fn query(values: &[&str]) -> RawValue {
+        RawValue::Query(values.iter().map(|value| value.to_string()).collect())
+    }
+
+    #[test]
+    fn test_query_kwargs() {
+        let args = vec![
+            py_arg("input", PyPrimitiveDataType::Str, false, None),
+            py_arg(
+                "top_k",
+                PyPrimitiveDataType::Int,
+                true,
+                Some(PyDefault::Int(5)),
+            ),
+            py_arg(
+                "ids",
+                PyPrimitiveDataType::List(Box::new(PyPrimitiveDataType::Int)),
+                false,
+                None,
+            ),
+            py_arg("offset", PyPrimitiveDataType::Int, true, None),
+            py_arg(
+                "threshold",
+                PyPrimitiveDataType::Float,
+                true,
+                Some(PyDefault::Expression),
+            ),
+        ];
+
+        let mut fields = HashMap::new();
+        fields.insert("input".to_string(), query(&["Paris"]));
+        fields.insert("ids".to_string(), query(&["1", "-2"]));
+
+        let kwargs = kwargs(&args, fields).unwrap();
+
+        assert_eq!(
+            Value::Object(kwargs),
+            json!({ "input": "Paris", "top_k": 5, "ids": [1, -2], "offset": null })
+        );
+    }
+
+    #[test]
+    fn test_invalid_fields() {
+        let args = vec![
+            py_arg("input", PyPrimitiveDataType::Str, false, None),
+            py_arg("top_k", PyPrimitiveDataType::Int, false, None),
+        ];
+
+        let mut fields = HashMap::new();
+        fields.insert(
+            "top_k".to_string(),
+            RawValue::Json(json!(9223372036854775808u64)),
+        );
+        fields.insert("foo".to_string(), RawValue::Json(json!("bar")));
+
+        assert_eq!(
+            kwargs(&args, fields).unwrap_err(),
+            vec![
+                FieldError {
+                    name: "input".into(),
+                    expected: Some("str".into()),
+                    reason: "missing".into()
+                },
+                FieldError {
+                    name: "top_k".into(),
+                    expected: Some("int".into()),
+                    reason: "number too large to fit in a 64 bits signed integer".into()
+                },
+                FieldError {
+                    name: "foo".into(),
+                    expected: None,
+                    reason: "unexpected".into()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_struct_fields() {
+        let span = PyStruct {
+            ident: "Struct_linker__Span".into(),
+            class_name: "Span".into(),
+            kind: PyStructKind::TypedDict,
+            fields: vec![
+                py_arg("start", PyPrimitiveDataType::Int, false, None),
+                py_arg("end", PyPrimitiveDataType::Int, false, None),
+                py_arg(
+                    "label",
+                    PyPrimitiveDataType::Str,
+                    true,
+                    Some(PyDefault::Str("ENT".into())),
+                ),
+            ],
+        };
+        let spans = py_arg(
+            "spans",
+            PyPrimitiveDataType::List(Box::new(PyPrimitiveDataType::Struct(span))),
+            false,
+            None,
+        );
+
+        let mut fields = HashMap::new();
+        fields.insert(
+            "spans".to_string(),
+            RawValue::Json(json!([{ "start": 0, "end": 5 }])),
+        );
+
+        assert_eq!(
+            Value::Object(kwargs(std::slice::from_ref(&spans), fields).unwrap()),
+            json!({ "spans": [{ "start": 0, "end": 5, "label": "ENT" }] })
+        );
+
+        let mut fields = HashMap::new();
+        fields.insert(
+            "spans".to_string(),
+            RawValue::Json(json!([{ "start": 0, "end": "5", "id": 1 }])),
+        );
+
+        assert_eq!(
+            kwargs(&[spans], fields).unwrap_err(),
+            vec![FieldError {
+                name: "spans".into(),
+                expected: Some("list[Span]".into()),
+                reason: "item 0: field `end`: expected int, field `id`: unexpected".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_constraints() {
+        let mut top_k = py_arg("top_k", PyPrimitiveDataType::Int, false, None);
+        top_k.constraints = vec![PyConstraint::Range {
+            min: Some(1.0),
+            max: Some(50.0),
+        }];
+        let mut text = py_arg("text", PyPrimitiveDataType::Str, false, None);
+        text.constraints = vec![PyConstraint::MaxLen(3)];
+
+        let mut fields = HashMap::new();
+        fields.insert("top_k".to_string(), query(&["51"]));
+        fields.insert("text".to_string(), RawValue::Json(json!("été")));
+
+        assert_eq!(
+            kwargs(&[top_k, text], fields).unwrap_err(),
+            vec![FieldError {
+                name: "top_k".into(),
+                expected: Some("int".into()),
+                reason: "value must be between 1 and 50".into(),
+            }]
+        );
+    }
+}
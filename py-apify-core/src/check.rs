@@ -0,0 +1,179 @@
+use rustpython_parser::ast::{Located, StatementType, Suite};
+use rustpython_parser::parser;
+
+use crate::diagnostic::Diagnostic;
+use crate::py_arg::{
+    collect_func_args, get_func_by_name, parse_annotation, PyClasses, PyPrimitiveDataType,
+};
+
+const RUST_KEYWORDS: &[&str] = &[
+    "_", "abstract", "as", "async", "await", "become", "box", "break", "const", "continue",
+    "crate", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Returns `true` if one of the statements returns a value. Nested function
+/// and class definitions are not inspected.
+fn returns_value(statements: &Suite) -> bool {
+    statements.iter().any(|statement| match &statement.node {
+        StatementType::Return { value, .. } => value.is_some(),
+        StatementType::If { body, orelse, .. }
+        | StatementType::While { body, orelse, .. }
+        | StatementType::For { body, orelse, .. } => {
+            returns_value(body) || orelse.as_ref().is_some_and(returns_value)
+        }
+        StatementType::With { body, .. } => returns_value(body),
+        StatementType::Try {
+            body,
+            handlers,
+            orelse,
+            finalbody,
+            ..
+        } => {
+            returns_value(body)
+                || handlers.iter().any(|handler| returns_value(&handler.body))
+                || orelse.as_ref().is_some_and(returns_value)
+                || finalbody.as_ref().is_some_and(returns_value)
+        }
+        _ => false,
+    })
+}
+
+/// Returns the class and the name of the first struct field named after a
+/// Rust keyword, nested structs included.
+fn keyword_field(data_type: &PyPrimitiveDataType) -> Option<(&str, &str)> {
+    match data_type {
+        PyPrimitiveDataType::List(item_data_type) => keyword_field(item_data_type),
+        PyPrimitiveDataType::Struct(py_struct) => py_struct.fields.iter().find_map(|field| {
+            if RUST_KEYWORDS.contains(&field.name.as_str()) {
+                Some((py_struct.class_name.as_str(), field.name.as_str()))
+            } else {
+                keyword_field(&field.data_type)
+            }
+        }),
+        _ => None,
+    }
+}
+
+fn check_func(func: &Located<StatementType>, classes: &PyClasses) -> Result<(), Diagnostic> {
+    for (arg, _) in collect_func_args(func) {
+        if RUST_KEYWORDS.contains(&arg.arg.as_str()) {
+            return Err(Diagnostic::at(
+                &arg.location,
+                format!(
+                    "parameter `{}` clashes with a Rust keyword, please rename it",
+                    arg.arg
+                ),
+            ));
+        }
+
+        if let Some(annotation) = &arg.annotation {
+            let (data_type, _, _) = parse_annotation(annotation, classes)?;
+
+            if let Some((class_name, field)) = keyword_field(&data_type) {
+                return Err(Diagnostic::at(
+                    &annotation.location,
+                    format!(
+                        "field `{}` of `{}` clashes with a Rust keyword, please rename it",
+                        field, class_name
+                    ),
+                ));
+            }
+        }
+    }
+
+    if let StatementType::FunctionDef { name, body, .. } = &func.node {
+        if !returns_value(body) {
+            return Err(Diagnostic::at(
+                &func.location,
+                format!("`{}` function never returns a value", name),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates a Python file before generating its route: the file must parse
+/// and contain a `func_name` function with supported annotations, parameter
+/// names usable as Rust identifiers and at least one `return <value>`.
+///
+/// `apify!` skips this pass when the `no-check` feature is enabled.
+pub fn check_py_code(py_code: &str, func_name: &str) -> Result<(), Diagnostic> {
+    let program = parser::parse_program(py_code)
+        .map_err(|e| Diagnostic::at(&e.location, e.error.to_string()))?;
+
+    let func = get_func_by_name(&program, func_name).ok_or_else(|| {
+        Diagnostic::new(format!(
+            "`{}` function not found. Your python file must contain a `{}` function",
+            func_name, func_name
+        ))
+    })?;
+
+    check_func(func, &PyClasses::from(&program))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_file() {
+        let py_code = "def call(input: str, top_k: int = 5):\n    if top_k > 1:\n        return input\n    return ''\n";
+
+        assert_eq!(check_py_code(py_code, "call"), Ok(()));
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        let error = check_py_code("def call(input:\n", "call").unwrap_err();
+
+        assert!(error.location.is_some());
+    }
+
+    #[test]
+    fn test_missing_return_value() {
+        let py_code =
+            "def call(input):\n    def inner():\n        return 1\n    print(input)\n    return\n";
+
+        let error = check_py_code(py_code, "call").unwrap_err();
+
+        assert_eq!(error.message, "`call` function never returns a value");
+        assert_eq!(error.location.map(|(row, _)| row), Some(1));
+    }
+
+    #[test]
+    fn test_rust_keyword_parameter() {
+        let py_code = "def call(input, type: str = 'ner'):\n    return input\n";
+
+        let error = check_py_code(py_code, "call").unwrap_err();
+
+        assert_eq!(
+            error.message,
+            "parameter `type` clashes with a Rust keyword, please rename it"
+        );
+    }
+
+    #[test]
+    fn test_rust_keyword_struct_field() {
+        let py_code = "class Entity(TypedDict):\n    type: str\n\nclass Document(TypedDict):\n    entities: list[Entity]\n\ndef call(doc: Document):\n    return doc\n";
+
+        let error = check_py_code(py_code, "call").unwrap_err();
+
+        assert_eq!(
+            error.message,
+            "field `type` of `Entity` clashes with a Rust keyword, please rename it"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_annotation() {
+        let py_code = "def call(input: set):\n    return input\n";
+
+        let error = check_py_code(py_code, "call").unwrap_err();
+
+        assert_eq!(error.message, "annotation `set` is not supported");
+    }
+}
@@ -0,0 +1,81 @@
+use rustpython_parser::ast::Location;
+use std::fmt;
+
+/// An error found while reading a Python file. It is reported as a
+/// `compile_error!` on the `apify!` invocation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub file_name: Option<String>,
+    /// Row and column in the Python file
+    pub location: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new<S: Into<String>>(message: S) -> Self {
+        Diagnostic {
+            file_name: None,
+            location: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn at<S: Into<String>>(location: &Location, message: S) -> Self {
+        Diagnostic {
+            file_name: None,
+            location: Some((location.row(), location.column())),
+            message: message.into(),
+        }
+    }
+
+    /// Attaches the name of the Python file, unless it is already known.
+    pub fn in_file(mut self, file_name: &str) -> Self {
+        self.file_name.get_or_insert_with(|| file_name.to_string());
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(file_name) = &self.file_name {
+            write!(f, "{}:", file_name)?;
+        }
+
+        if let Some((row, column)) = self.location {
+            write!(f, "{}:{}:", row, column)?;
+        }
+
+        if self.file_name.is_some() || self.location.is_some() {
+            write!(f, " ")?;
+        }
+
+        write!(f, "{}", self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnostic_display() {
+        let diagnostic = Diagnostic {
+            file_name: None,
+            location: Some((14, 18)),
+            message: "annotation `dict` is not supported".into(),
+        };
+
+        assert_eq!(
+            diagnostic.to_string(),
+            "14:18: annotation `dict` is not supported"
+        );
+        assert_eq!(
+            diagnostic.in_file("test.py").to_string(),
+            "test.py:14:18: annotation `dict` is not supported"
+        );
+        assert_eq!(
+            Diagnostic::new("invalid glob pattern").to_string(),
+            "invalid glob pattern"
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use rustpython_parser::ast::{ExpressionType, Located, StatementType, StringGroup};
+use rustpython_parser::parser;
+use std::collections::HashMap;
+
+use crate::diagnostic::Diagnostic;
+use crate::py_arg::get_func_by_name;
+
+/// Google-style sections documenting parameters, NumPy-style sections have
+/// the same names without the colon.
+const ARGS_SECTIONS: &[&str] = &[
+    "Args",
+    "Arguments",
+    "Parameters",
+    "Params",
+    "Keyword Args",
+    "Keyword Arguments",
+    "Other Parameters",
+];
+
+/// Other Google-style sections, they end the description and the parameters.
+const OTHER_SECTIONS: &[&str] = &[
+    "Attributes",
+    "Example",
+    "Examples",
+    "Note",
+    "Notes",
+    "Raises",
+    "References",
+    "Return",
+    "Returns",
+    "See Also",
+    "Todo",
+    "Warning",
+    "Warnings",
+    "Yield",
+    "Yields",
+];
+
+/// Documentation of a function, read from its docstring.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Docstring {
+    /// Text before the first section
+    pub description: Option<String>,
+    /// Descriptions of the `Args:` or `Parameters` section, by parameter name
+    pub args: HashMap<String, String>,
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Removes the indentation of a docstring, like `inspect.cleandoc`.
+fn clean_lines(docstring: &str) -> Vec<String> {
+    let lines: Vec<String> = docstring
+        .lines()
+        .map(|line| line.replace('\t', "        "))
+        .collect();
+
+    let margin = lines
+        .iter()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| indentation(line))
+        .min()
+        .unwrap_or(0);
+
+    let mut lines: Vec<String> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| match i {
+            0 => line.trim_start().to_string(),
+            _ => line
+                .get(margin..)
+                .unwrap_or_default()
+                .trim_end()
+                .to_string(),
+        })
+        .collect();
+
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+
+    let first_line = lines.iter().position(|line| !line.trim().is_empty());
+    lines.split_off(first_line.unwrap_or(lines.len()))
+}
+
+/// Name of the section starting at line `i` and the number of lines of its
+/// header: 1 for `Args:`, 2 for a NumPy-style title underlined with dashes.
+fn section_at(lines: &[String], i: usize) -> Option<(&str, usize)> {
+    let title = lines[i].trim();
+
+    let underlined = lines.get(i + 1).is_some_and(|line| {
+        let line = line.trim();
+        line.len() >= 3 && line.chars().all(|c| c == '-')
+    });
+
+    if underlined && !title.is_empty() {
+        return Some((title, 2));
+    }
+
+    title
+        .strip_suffix(':')
+        .filter(|name| ARGS_SECTIONS.contains(name) || OTHER_SECTIONS.contains(name))
+        .map(|name| (name, 1))
+}
+
+/// Parses the entries of a parameters section: `name (type): description` in
+/// the Google style, `name : type` followed by an indented description in the
+/// NumPy style.
+fn parse_args(lines: &[String], numpy_style: bool, args: &mut HashMap<String, String>) {
+    let entry_indentation = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| indentation(line))
+        .min()
+        .unwrap_or(0);
+
+    let mut entries: Vec<(Vec<String>, Vec<&str>)> = vec![];
+
+    for line in lines.iter().filter(|line| !line.trim().is_empty()) {
+        let text = line.trim();
+
+        if indentation(line) > entry_indentation {
+            if let Some((_, description)) = entries.last_mut() {
+                description.push(text);
+            }
+            continue;
+        }
+
+        let (names, rest) = match text.split_once(':') {
+            Some((names, rest)) => (names, rest.trim()),
+            None => (text, ""),
+        };
+
+        // `x, y : int` documents two parameters, `top_k (int)` one
+        let names = names
+            .split(',')
+            .filter_map(|name| {
+                name.split(|c: char| c == '(' || c.is_whitespace())
+                    .find(|name| !name.is_empty())
+            })
+            .map(|name| name.trim_start_matches('*').to_string())
+            .collect();
+
+        let description = match numpy_style || rest.is_empty() {
+            true => vec![],
+            false => vec![rest],
+        };
+
+        entries.push((names, description));
+    }
+
+    for (names, description) in entries {
+        if description.is_empty() {
+            continue;
+        }
+
+        for name in names {
+            args.insert(name, description.join(" "));
+        }
+    }
+}
+
+impl Docstring {
+    pub fn parse(docstring: &str) -> Docstring {
+        let lines = clean_lines(docstring);
+
+        let sections_start = (0..lines.len())
+            .find(|i| section_at(&lines, *i).is_some())
+            .unwrap_or(lines.len());
+
+        let description = lines[..sections_start].join("\n").trim().to_string();
+
+        let mut args = HashMap::new();
+        let mut i = sections_start;
+
+        while let Some((name, header_lines)) = lines.get(i).and_then(|_| section_at(&lines, i)) {
+            let start = i + header_lines;
+            let end = (start..lines.len())
+                .find(|i| section_at(&lines, *i).is_some())
+                .unwrap_or(lines.len());
+
+            if ARGS_SECTIONS.contains(&name) {
+                parse_args(&lines[start..end], header_lines == 2, &mut args);
+            }
+
+            i = end;
+        }
+
+        Docstring {
+            description: Some(description).filter(|description| !description.is_empty()),
+            args,
+        }
+    }
+}
+
+/// Docstring of a function: the string literal starting its body.
+pub fn func_docstring(func: &Located<StatementType>) -> Option<&str> {
+    let body = match &func.node {
+        StatementType::FunctionDef { body, .. } => body,
+        _ => return None,
+    };
+
+    match &body.first()?.node {
+        StatementType::Expression { expression } => match &expression.node {
+            ExpressionType::String {
+                value: StringGroup::Constant { value },
+            } => Some(value.as_str()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Reads the documentation of the `func_name` function, empty when it has no
+/// docstring.
+pub fn get_func_doc(py_code: &str, func_name: &str) -> Result<Docstring, Diagnostic> {
+    let program = parser::parse_program(py_code)
+        .map_err(|e| Diagnostic::at(&e.location, e.error.to_string()))?;
+
+    Ok(get_func_by_name(&program, func_name)
+        .and_then(func_docstring)
+        .map(Docstring::parse)
+        .unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_google_style() {
+        let py_code = "def call(text, top_k: int = 5, *labels):\n    \"\"\"Finds the named entities of a text.\n\n    Entities are linked to Wikidata.\n\n    Args:\n        text: The text to analyse,\n            in any language.\n        top_k (int): Maximum number of candidates.\n        *labels: Labels to keep.\n\n    Returns:\n        A list of entities.\n    \"\"\"\n    return []\n";
+
+        let doc = get_func_doc(py_code, "call").unwrap();
+
+        assert_eq!(
+            doc.description.as_deref(),
+            Some("Finds the named entities of a text.\n\nEntities are linked to Wikidata.")
+        );
+        assert_eq!(doc.args.len(), 3);
+        assert_eq!(doc.args["text"], "The text to analyse, in any language.");
+        assert_eq!(doc.args["top_k"], "Maximum number of candidates.");
+        assert_eq!(doc.args["labels"], "Labels to keep.");
+    }
+
+    #[test]
+    fn test_numpy_style() {
+        let docstring = "Links the mentions.\n\n    Parameters\n    ----------\n    text : str\n        The text to analyse.\n    start, end : int\n        Bounds of the mention.\n    threshold\n\n    Returns\n    -------\n    list\n        The candidates.\n    ";
+
+        let doc = Docstring::parse(docstring);
+
+        assert_eq!(doc.description.as_deref(), Some("Links the mentions."));
+        assert_eq!(doc.args.len(), 3);
+        assert_eq!(doc.args["text"], "The text to analyse.");
+        assert_eq!(doc.args["start"], "Bounds of the mention.");
+        assert_eq!(doc.args["end"], "Bounds of the mention.");
+    }
+
+    #[test]
+    fn test_missing_docstring() {
+        let doc = get_func_doc("def call(text):\n    'not' + 'a docstring'\n", "call").unwrap();
+
+        assert_eq!(doc, Docstring::default());
+    }
+}
@@ -0,0 +1,219 @@
+//! Reloading of the Python files served by the `apify!` routes in development
+//! mode (`hot-reload` feature).
+
+use pyo3::types::{PyDict, PyModule};
+use pyo3::Python;
+use std::borrow::Cow;
+
+use crate::diagnostic::Diagnostic;
+use crate::py_arg::{get_func_args, PyArg};
+
+fn param_names(args: &[PyArg]) -> String {
+    args.iter()
+        .map(|arg| arg.name.as_str())
+        .collect::<Vec<&str>>()
+        .join(", ")
+}
+
+/// Describes how the signature of `call` changed between two versions of a
+/// Python file, `None` when the parameters, their annotations, defaults and
+/// constraints are the same.
+pub fn signature_change(served_code: &str, new_code: &str) -> Result<Option<String>, Diagnostic> {
+    let served_args = get_func_args(served_code.to_string(), "call")?;
+    let new_args = get_func_args(new_code.to_string(), "call")?;
+
+    if param_names(&served_args) != param_names(&new_args) {
+        return Ok(Some(format!(
+            "the parameters of `call` changed from ({}) to ({})",
+            param_names(&served_args),
+            param_names(&new_args)
+        )));
+    }
+
+    Ok(served_args
+        .iter()
+        .zip(&new_args)
+        .find(|(served_arg, new_arg)| served_arg != new_arg)
+        .map(|(served_arg, _)| {
+            format!(
+                "the annotation, default or constraints of `{}` changed",
+                served_arg.name
+            )
+        }))
+}
+
+/// Code of a Python file to import at startup: the file on disk, unless it
+/// can't be read or the signature of its `call` function differs from the
+/// one the routes were generated from. The code embedded at build time is
+/// served in that case, like a rejected reload.
+pub fn startup_code(
+    served_code: &'static str,
+    disk_code: std::io::Result<String>,
+    file_name: &str,
+) -> Cow<'static, str> {
+    let disk_code = match disk_code {
+        Ok(disk_code) => disk_code,
+        Err(e) => {
+            log::warn!(
+                "Failed to read {}, serving the built version: {}",
+                file_name,
+                e
+            );
+            return Cow::Borrowed(served_code);
+        }
+    };
+
+    match signature_change(served_code, &disk_code) {
+        Ok(None) => Cow::Owned(disk_code),
+        Ok(Some(change)) => {
+            log::warn!(
+                "{}: {}, serving the built version until the server is rebuilt",
+                file_name,
+                change
+            );
+            Cow::Borrowed(served_code)
+        }
+        Err(e) => {
+            log::warn!("{}: {}, serving the built version", file_name, e);
+            Cow::Borrowed(served_code)
+        }
+    }
+}
+
+/// Imports the new version of a Python file under a temporary name, then swaps
+/// it in `sys.modules`. The running version is kept if the import fails or if
+/// the signature of `call` differs from the one the routes were generated
+/// from, since the generated forms can't change without a rebuild.
+pub fn reload_py_module(
+    py: Python,
+    served_code: &str,
+    new_code: &str,
+    file_name: &str,
+    module_name: &str,
+) -> Result<(), String> {
+    if let Some(change) = signature_change(served_code, new_code).map_err(|e| e.to_string())? {
+        return Err(format!(
+            "{}, rebuild the server to apply this change",
+            change
+        ));
+    }
+
+    let staging_name = format!("{}__reload", module_name);
+
+    let result = (|| {
+        let sys_modules: &PyDict = py.import("sys")?.getattr("modules")?.downcast()?;
+        let module = PyModule::from_code(py, new_code, file_name, &staging_name)?;
+        sys_modules.del_item(&staging_name)?;
+
+        sys_modules.set_item(module_name, module)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SERVED_CODE: &str = "from typing import Annotated\nfrom py_apify import Range\n\ndef call(text: str, top_k: Annotated[int, Range(1, 50)] = 5):\n    return 'served'\n";
+
+    fn call_served_module(py: Python) -> String {
+        py.import("py_apify_reloaded")
+            .and_then(|module| module.getattr("call"))
+            .and_then(|call| call.call1(("Paris",)))
+            .and_then(|result| result.extract())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_signature_change() {
+        let change = |new_code: &str| signature_change(SERVED_CODE, new_code).unwrap();
+
+        assert_eq!(
+            change("def call(text: str):\n    return 'new'\n").unwrap(),
+            "the parameters of `call` changed from (text, top_k) to (text)"
+        );
+        assert_eq!(
+            change("def call(text: str, top_k: int = 5):\n    return 'new'\n").unwrap(),
+            "the annotation, default or constraints of `top_k` changed"
+        );
+        assert_eq!(
+            change(&SERVED_CODE.replace("= 5", "= 10")).unwrap(),
+            "the annotation, default or constraints of `top_k` changed"
+        );
+        assert_eq!(
+            change(&SERVED_CODE.replace("str,", "Optional[str],")).unwrap(),
+            "the annotation, default or constraints of `text` changed"
+        );
+        assert_eq!(change(&SERVED_CODE.replace("'served'", "'new'")), None);
+    }
+
+    #[test]
+    fn test_startup_code() {
+        let startup = |disk_code: String| startup_code(SERVED_CODE, Ok(disk_code), "served.py");
+
+        let edited_body = SERVED_CODE.replace("'served'", "'edited'");
+        assert_eq!(startup(edited_body.clone()), edited_body);
+        assert_eq!(startup(SERVED_CODE.replace("= 5", "= 10")), SERVED_CODE);
+        assert_eq!(startup("def call(:\n".to_string()), SERVED_CODE);
+
+        let missing = std::io::Error::from(std::io::ErrorKind::NotFound);
+        assert_eq!(
+            startup_code(SERVED_CODE, Err(missing), "served.py"),
+            SERVED_CODE
+        );
+    }
+
+    #[test]
+    fn test_rejected_reload() {
+        pyo3::prepare_freethreaded_python();
+
+        Python::with_gil(|py| {
+            let py_apify_module = PyModule::from_code(
+                py,
+                crate::py_module::PY_APIFY_MODULE,
+                "py_apify.py",
+                crate::py_module::PY_APIFY_MODULE_NAME,
+            )
+            .unwrap();
+            py.import("sys")
+                .and_then(|sys| sys.getattr("modules"))
+                .and_then(|modules| {
+                    modules.set_item(crate::py_module::PY_APIFY_MODULE_NAME, py_apify_module)
+                })
+                .unwrap();
+
+            PyModule::from_code(py, SERVED_CODE, "reloaded.py", "py_apify_reloaded").unwrap();
+
+            let new_code = SERVED_CODE
+                .replace("Range(1, 50)", "Range(1, 100)")
+                .replace("'served'", "'rejected'");
+            let e = reload_py_module(
+                py,
+                SERVED_CODE,
+                &new_code,
+                "reloaded.py",
+                "py_apify_reloaded",
+            )
+            .unwrap_err();
+
+            assert_eq!(
+                e,
+                "the annotation, default or constraints of `top_k` changed, rebuild the server to apply this change"
+            );
+            assert_eq!(call_served_module(py), "served");
+
+            let new_code = SERVED_CODE.replace("'served'", "'reloaded'");
+            reload_py_module(
+                py,
+                SERVED_CODE,
+                &new_code,
+                "reloaded.py",
+                "py_apify_reloaded",
+            )
+            .unwrap();
+
+            assert_eq!(call_served_module(py), "reloaded");
+        });
+    }
+}
@@ -0,0 +1,12 @@
+//! Python signature parsing and runtime helpers shared by the `apify!` macro
+//! and the `py-apify` command line.
+
+pub mod check;
+pub mod diagnostic;
+pub mod docstring;
+pub mod hot_reload;
+pub mod problem;
+pub mod py_arg;
+pub mod py_module;
+pub mod python_file;
+pub mod result;
@@ -0,0 +1,95 @@
+//! RFC 7807 `application/problem+json` documents answering the failed
+//! requests of the `apify!` routes and of `py-apify serve`.
+
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Header carrying the request id, taken from the request when the client
+/// sets a valid one and always set on the response.
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// Top-level and sub type of `application/problem+json`.
+pub const PROBLEM_MEDIA_TYPE: (&str, &str) = ("application", "problem+json");
+
+/// Builds the problem document of an error. `kind` ends its `type` URN and
+/// the request id its `instance` URN, extension members like `exception` or
+/// `fields` are added by the caller.
+pub fn problem(kind: &str, title: &str, status: u16, detail: &str, request_id: &str) -> Value {
+    json!({
+        "type": format!("urn:py-apify:error:{}", kind),
+        "title": title,
+        "status": status,
+        "detail": detail,
+        "instance": format!("urn:py-apify:request:{}", request_id),
+    })
+}
+
+/// Whether a request id sent by a client can be reused in the logs and in
+/// the `instance` of a problem.
+pub fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 128
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.:".contains(c))
+}
+
+/// Start time of the process in milliseconds followed by a counter, so ids
+/// stay unique across restarts.
+pub fn generate_request_id() -> String {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+    static STARTED_AT: AtomicU64 = AtomicU64::new(0);
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default();
+
+    let started_at = match STARTED_AT.compare_exchange(0, now, Ordering::Relaxed, Ordering::Relaxed)
+    {
+        Ok(_) => now,
+        Err(started_at) => started_at,
+    };
+
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+
+    format!("{:x}-{:06x}", started_at, id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_problem() {
+        assert_eq!(
+            problem(
+                "not-found",
+                "Not found",
+                404,
+                "No route matches GET /a",
+                "17f-000001"
+            ),
+            json!({
+                "type": "urn:py-apify:error:not-found",
+                "title": "Not found",
+                "status": 404,
+                "detail": "No route matches GET /a",
+                "instance": "urn:py-apify:request:17f-000001",
+            })
+        );
+    }
+
+    #[test]
+    fn test_request_ids() {
+        let first = generate_request_id();
+        let second = generate_request_id();
+
+        assert_ne!(first, second);
+        assert!(is_valid_request_id(&first));
+        assert!(!is_valid_request_id(""));
+        assert!(!is_valid_request_id("a b"));
+        assert!(!is_valid_request_id(&"a".repeat(129)));
+    }
+}
@@ -0,0 +1,176 @@
+"""Helpers importable as `py_apify` by the Python files served by py-apify."""
+
+import io
+import logging
+import os
+import sys
+import threading
+
+
+class HTTPError(Exception):
+    """Raise from `call` to answer with an HTTP error status.
+
+    `raise HTTPError(404, "unknown entity")` answers with a 404 and keeps the
+    detail in the JSON body. A `ValueError` raised from `call` is answered
+    with a 422, any other exception with a 500.
+    """
+
+    def __init__(self, status, detail=""):
+        super().__init__(status, detail)
+        self.status = status
+        self.detail = detail
+
+    def __str__(self):
+        return str(self.detail)
+
+
+class UploadFile:
+    """File uploaded in a multipart/form-data request.
+
+    Annotate a `call` parameter with `py_apify.UploadFile` to receive the
+    file with its name and content type, or with `bytes` to only receive its
+    content. `filename` is the name sent by the client, never use it as a
+    path without sanitizing it.
+    """
+
+    def __init__(self, filename, content_type, data):
+        self.filename = filename
+        self.content_type = content_type
+        self.file = io.BytesIO(data)
+
+    def read(self, size=-1):
+        return self.file.read(size)
+
+    def seek(self, offset, whence=io.SEEK_SET):
+        return self.file.seek(offset, whence)
+
+    def __repr__(self):
+        return "UploadFile(filename={!r}, content_type={!r})".format(
+            self.filename, self.content_type
+        )
+
+
+# Constraints of `Annotated` parameters, read from the source by py-apify and
+# checked before `call` runs: `top_k: Annotated[int, Range(1, 50)]`.
+
+
+class Range:
+    """Bounds of a number, both included. Either one can be `None`."""
+
+    def __init__(self, min=None, max=None):
+        self.min = min
+        self.max = max
+
+
+class MinLen:
+    """Minimum length of a string or a list."""
+
+    def __init__(self, length):
+        self.length = length
+
+
+class MaxLen:
+    """Maximum length of a string or a list."""
+
+    def __init__(self, length):
+        self.length = length
+
+
+_local = threading.local()
+
+
+def request_id():
+    """Id of the request served by the current thread, also returned in the
+    `X-Request-Id` header. `None` outside of a request."""
+    return getattr(_local, "request_id", None)
+
+
+def _set_request_id(request_id):
+    _local.request_id = request_id
+
+
+# Bridge to the Rust `log` crate, set up by `_install` when the module is
+# injected in the interpreter.
+
+_files = {}
+_rust_log = None
+
+
+def _register_file(file_name, file_stem):
+    """Logs and prints coming from `file_name` use `file_stem` as target."""
+    _files[file_name] = file_stem
+
+
+def _caller_target():
+    frame = sys._getframe(2)
+
+    while frame is not None:
+        target = _files.get(frame.f_code.co_filename)
+
+        if target is not None:
+            return target
+
+        frame = frame.f_back
+
+    return "python"
+
+
+class _RustHandler(logging.Handler):
+    """Forwards the `logging` records to the Rust `log` crate. Records of the
+    served files use the file stem as target, other records the logger name."""
+
+    def emit(self, record):
+        try:
+            target = _files.get(record.pathname, record.name)
+            _rust_log(record.levelno, target, self.format(record), request_id())
+        except Exception:
+            self.handleError(record)
+
+
+class _RustStream:
+    """Replaces `sys.stdout` and `sys.stderr`, each line written is logged with
+    `level`."""
+
+    def __init__(self, level):
+        self.level = level
+        self.buffer = ""
+
+    def write(self, text):
+        self.buffer += text
+        *lines, self.buffer = self.buffer.split("\n")
+
+        for line in lines:
+            _rust_log(self.level, _caller_target(), line, request_id())
+
+        return len(text)
+
+    def flush(self):
+        if self.buffer:
+            _rust_log(self.level, _caller_target(), self.buffer, request_id())
+            self.buffer = ""
+
+    def isatty(self):
+        return False
+
+
+def _install(rust_log, level):
+    global _rust_log
+    _rust_log = rust_log
+
+    logging.root.addHandler(_RustHandler())
+    logging.root.setLevel(level)
+
+    sys.stdout = _RustStream(logging.INFO)
+    sys.stderr = _RustStream(logging.WARNING)
+
+
+def _worker_channel():
+    """Moves the standard output of a worker process to a new file descriptor,
+    returned as a file, and points file descriptor 1 at stderr. Nothing
+    printed by the served file, not even by C extensions, can then corrupt the
+    responses sent to the parent process."""
+    sys.stdout.flush()
+    channel = os.fdopen(os.dup(1), "w")
+    os.dup2(2, 1)
+
+    return channel
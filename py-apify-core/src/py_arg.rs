@@ -0,0 +1,1356 @@
+use proc_macro2::{Ident, Literal, Span, TokenStream as TokenStream2};
+use quote::quote;
+use rustpython_parser::ast::{
+    ExpressionType, Located, Number, Operator, Parameter, Program, StatementType, StringGroup,
+    UnaryOperator, Varargs,
+};
+use rustpython_parser::parser;
+use serde_json::{json, Map, Value};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::diagnostic::Diagnostic;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PyPrimitiveDataType {
+    Str,
+    Float,
+    Int,
+    Bool,
+    List(Box<PyPrimitiveDataType>),
+    /// Content of a file uploaded in a multipart/form-data request
+    Bytes,
+    /// File uploaded in a multipart/form-data request, received as a
+    /// `py_apify.UploadFile` with its name and content type
+    UploadFile,
+    /// `Literal["a", "b"]` or a module-level `Enum` subclass
+    Enum(PyEnum),
+    /// Module-level `TypedDict` or dataclass, received as a JSON object
+    Struct(PyStruct),
+}
+
+/// String values accepted by a `Literal[...]` annotation or by a module-level
+/// `Enum` subclass. The route only accepts these values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyEnum {
+    /// Identifier of the generated Rust enum, see `PyArg::scope_idents`
+    pub ident: String,
+    /// Name of the `Enum` subclass, `None` for a `Literal`
+    pub class_name: Option<String>,
+    pub values: Vec<String>,
+}
+
+impl PyEnum {
+    /// Identifiers of the Rust variants, in the order of `values`. Values
+    /// aren't always valid identifiers, the variants are numbered instead.
+    pub fn variant_idents(&self) -> Vec<Ident> {
+        (0..self.values.len())
+            .map(|i| Ident::new(&format!("V{}", i), Span::call_site()))
+            .collect()
+    }
+
+    /// Identifier of the Rust variant matching `value`.
+    pub fn variant_ident(&self, value: &str) -> Option<Ident> {
+        let i = self.values.iter().position(|v| v == value)?;
+        self.variant_idents().into_iter().nth(i)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PyStructKind {
+    /// `class Span(TypedDict)`, passed to `call` as a dict
+    TypedDict,
+    /// `@dataclass class Span`, passed to `call` as an instance of the class
+    Dataclass,
+}
+
+/// Fields of a module-level `TypedDict` or dataclass, read from its annotated
+/// class attributes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyStruct {
+    /// Identifier of the generated Rust struct, see `PyArg::scope_idents`
+    pub ident: String,
+    pub class_name: String,
+    pub kind: PyStructKind,
+    pub fields: Vec<PyArg>,
+}
+
+/// Module-level classes of a file usable as annotations: the members of the
+/// `Enum` subclasses and the definitions of the `TypedDict` and dataclasses,
+/// by class name. `Enum` subclasses with a member whose value is not a string
+/// are left out.
+#[derive(Debug, Default)]
+pub struct PyClasses<'a> {
+    enums: HashMap<String, Vec<String>>,
+    structs: HashMap<String, (PyStructKind, &'a Located<StatementType>)>,
+    /// Structs being parsed, to reject the ones containing themselves
+    parsing: RefCell<Vec<String>>,
+}
+
+fn is_enum_base(base: &Located<ExpressionType>) -> bool {
+    match &base.node {
+        ExpressionType::Identifier { name, .. } => name == "Enum" || name == "StrEnum",
+        ExpressionType::Attribute { value, name, .. } => {
+            matches!(&value.node, ExpressionType::Identifier { name: module, .. } if module == "enum")
+                && (name == "Enum" || name == "StrEnum")
+        }
+        _ => false,
+    }
+}
+
+/// Value of an `Enum` member: `Some(None)` when it is not a string, `None`
+/// for statements that are not members.
+fn enum_member_value(statement: &Located<StatementType>) -> Option<Option<String>> {
+    let (targets, value) = match &statement.node {
+        StatementType::Assign { targets, value } => (targets, value),
+        _ => return None,
+    };
+
+    let is_private = targets.iter().any(|target| {
+        matches!(&target.node, ExpressionType::Identifier { name } if name.starts_with('_'))
+    });
+
+    match &value.node {
+        _ if is_private => None,
+        ExpressionType::String {
+            value: StringGroup::Constant { value },
+        } => Some(Some(value.clone())),
+        _ => Some(None),
+    }
+}
+
+/// `@dataclass`, `@dataclasses.dataclass` or a call of one of them.
+fn is_dataclass_decorator(decorator: &Located<ExpressionType>) -> bool {
+    match &decorator.node {
+        ExpressionType::Call { function, .. } => is_dataclass_decorator(function),
+        ExpressionType::Identifier { name } => name == "dataclass",
+        ExpressionType::Attribute { value, name, .. } => {
+            matches!(&value.node, ExpressionType::Identifier { name: module } if module == "dataclasses")
+                && name == "dataclass"
+        }
+        _ => false,
+    }
+}
+
+impl<'a> From<&'a Program> for PyClasses<'a> {
+    fn from(program: &'a Program) -> PyClasses<'a> {
+        let mut classes = PyClasses::default();
+
+        for statement in &program.statements {
+            let (name, body, bases, decorator_list) = match &statement.node {
+                StatementType::ClassDef {
+                    name,
+                    body,
+                    bases,
+                    decorator_list,
+                    ..
+                } => (name, body, bases, decorator_list),
+                _ => continue,
+            };
+
+            if bases.iter().any(is_enum_base) {
+                if let Some(values) = body
+                    .iter()
+                    .filter_map(enum_member_value)
+                    .collect::<Option<Vec<String>>>()
+                    .filter(|values| !values.is_empty())
+                {
+                    classes.enums.insert(name.clone(), values);
+                }
+            } else if bases
+                .iter()
+                .any(|base| annotation_name(base) == Some("TypedDict"))
+            {
+                classes
+                    .structs
+                    .insert(name.clone(), (PyStructKind::TypedDict, statement));
+            } else if decorator_list.iter().any(is_dataclass_decorator) {
+                classes
+                    .structs
+                    .insert(name.clone(), (PyStructKind::Dataclass, statement));
+            }
+        }
+
+        classes
+    }
+}
+
+impl PyClasses<'_> {
+    /// Parses a field of a struct. `ClassVar` attributes are not fields.
+    fn parse_field(
+        &self,
+        class_name: &str,
+        name: &str,
+        annotation: &Located<ExpressionType>,
+        default: Option<PyDefault>,
+    ) -> Result<Option<PyArg>, Diagnostic> {
+        if let ExpressionType::Subscript { a, .. } = &annotation.node {
+            if annotation_name(a) == Some("ClassVar") {
+                return Ok(None);
+            }
+        }
+
+        let (data_type, nullable, constraints) = parse_annotation(annotation, self)?;
+
+        if data_type.is_upload() {
+            return Err(unsupported_annotation(annotation));
+        }
+
+        // JSON bodies are not validated field by field, nested constraints
+        // would only be checked in query strings
+        if !constraints.is_empty() {
+            return Err(Diagnostic::at(
+                &annotation.location,
+                format!(
+                    "`Annotated` constraints are not supported in the fields of `{}`",
+                    class_name
+                ),
+            ));
+        }
+
+        Ok(Some(PyArg {
+            name: name.to_string(),
+            data_type,
+            optional: nullable || default.is_some(),
+            default,
+            constraints,
+            doc: None,
+        }))
+    }
+
+    fn parse_struct(
+        &self,
+        annotation: &Located<ExpressionType>,
+        class_name: &str,
+    ) -> Result<PyStruct, Diagnostic> {
+        let (kind, class) = self.structs[class_name];
+
+        if self.parsing.borrow().iter().any(|name| name == class_name) {
+            return Err(Diagnostic::at(
+                &annotation.location,
+                format!("`{}` can't contain itself", class_name),
+            ));
+        }
+
+        let (body, keywords) = match &class.node {
+            StatementType::ClassDef { body, keywords, .. } => (body, keywords),
+            _ => unreachable!(),
+        };
+
+        // keys of a `TypedDict` with `total=False` may be left out, Python
+        // then never sees them
+        let total = !keywords.iter().any(|keyword| {
+            keyword.name.as_deref() == Some("total")
+                && matches!(keyword.value.node, ExpressionType::False)
+        });
+
+        self.parsing.borrow_mut().push(class_name.to_string());
+
+        let fields = body
+            .iter()
+            .filter_map(|statement| match &statement.node {
+                StatementType::AnnAssign {
+                    target,
+                    annotation,
+                    value,
+                } => match &target.node {
+                    ExpressionType::Identifier { name } => Some((name, annotation, value)),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .map(|(name, annotation, value)| {
+                let default = match value {
+                    Some(value) => Some(PyDefault::from(value)),
+                    None if !total => Some(PyDefault::Expression),
+                    None => None,
+                };
+
+                self.parse_field(class_name, name, annotation, default)
+            })
+            .filter_map(Result::transpose)
+            .collect::<Result<Vec<PyArg>, Diagnostic>>();
+
+        self.parsing.borrow_mut().pop();
+
+        Ok(PyStruct {
+            ident: String::new(),
+            class_name: class_name.to_string(),
+            kind,
+            fields: fields?,
+        })
+    }
+}
+
+impl PyPrimitiveDataType {
+    fn from_name(name: &str) -> Option<PyPrimitiveDataType> {
+        match name {
+            "str" => Some(PyPrimitiveDataType::Str),
+            "int" => Some(PyPrimitiveDataType::Int),
+            "float" => Some(PyPrimitiveDataType::Float),
+            "bool" => Some(PyPrimitiveDataType::Bool),
+            "bytes" => Some(PyPrimitiveDataType::Bytes),
+            "UploadFile" => Some(PyPrimitiveDataType::UploadFile),
+            _ => None,
+        }
+    }
+
+    /// Name of the type in Python, e.g. `list[int]`.
+    pub fn python_name(&self) -> String {
+        match self {
+            PyPrimitiveDataType::Str => "str".into(),
+            PyPrimitiveDataType::Float => "float".into(),
+            PyPrimitiveDataType::Int => "int".into(),
+            PyPrimitiveDataType::Bool => "bool".into(),
+            PyPrimitiveDataType::List(item_data_type) => {
+                format!("list[{}]", item_data_type.python_name())
+            }
+            PyPrimitiveDataType::Bytes => "bytes".into(),
+            PyPrimitiveDataType::UploadFile => "UploadFile".into(),
+            PyPrimitiveDataType::Enum(py_enum) => match &py_enum.class_name {
+                Some(class_name) => class_name.clone(),
+                None => format!(
+                    "Literal[{}]",
+                    py_enum
+                        .values
+                        .iter()
+                        .map(|value| format!("{:?}", value))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+            },
+            PyPrimitiveDataType::Struct(py_struct) => py_struct.class_name.clone(),
+        }
+    }
+
+    /// Names the Rust enums and structs of this type, see `PyArg::scope_idents`.
+    fn scope(&mut self, prefix: &str, name: &str) {
+        match self {
+            PyPrimitiveDataType::Enum(py_enum) => {
+                py_enum.ident = format!("Enum_{}__{}", prefix, name);
+            }
+            PyPrimitiveDataType::List(item_data_type) => item_data_type.scope(prefix, name),
+            PyPrimitiveDataType::Struct(py_struct) => {
+                let prefix = format!("{}__{}", prefix, py_struct.class_name);
+
+                for field in &mut py_struct.fields {
+                    field.data_type.scope(&prefix, &field.name);
+                }
+
+                py_struct.ident = format!("Struct_{}", prefix);
+            }
+            _ => {}
+        }
+    }
+
+    /// Collects the enums and structs of this type, the ones of the struct
+    /// fields included.
+    pub fn named_types<'a>(&'a self, types: &mut Vec<&'a PyPrimitiveDataType>) {
+        match self {
+            PyPrimitiveDataType::List(item_data_type) => item_data_type.named_types(types),
+            PyPrimitiveDataType::Enum(_) => types.push(self),
+            PyPrimitiveDataType::Struct(py_struct) => {
+                for field in &py_struct.fields {
+                    field.data_type.named_types(types);
+                }
+
+                types.push(self);
+            }
+            _ => {}
+        }
+    }
+
+    /// Identifier of the generated Rust enum or struct of this type.
+    pub fn named_type_ident(&self) -> Option<&str> {
+        match self {
+            PyPrimitiveDataType::Enum(py_enum) => Some(&py_enum.ident),
+            PyPrimitiveDataType::Struct(py_struct) => Some(&py_struct.ident),
+            _ => None,
+        }
+    }
+
+    /// The enum accepted by this type or by its items.
+    pub fn py_enum(&self) -> Option<&PyEnum> {
+        match self {
+            PyPrimitiveDataType::Enum(py_enum) => Some(py_enum),
+            PyPrimitiveDataType::List(item_data_type) => item_data_type.py_enum(),
+            _ => None,
+        }
+    }
+
+    /// `true` for the types only sent in a multipart/form-data request.
+    pub fn is_upload(&self) -> bool {
+        matches!(
+            self,
+            PyPrimitiveDataType::Bytes | PyPrimitiveDataType::UploadFile
+        )
+    }
+}
+
+/// Renders an annotation back to Python source, for error messages.
+fn annotation_to_string(annotation: &Located<ExpressionType>) -> String {
+    match &annotation.node {
+        ExpressionType::Identifier { name, .. } => name.clone(),
+        ExpressionType::Attribute { value, name, .. } => {
+            format!("{}.{}", annotation_to_string(value), name)
+        }
+        ExpressionType::Subscript { a, b, .. } => {
+            format!("{}[{}]", annotation_to_string(a), annotation_to_string(b))
+        }
+        ExpressionType::Tuple { elements, .. } | ExpressionType::List { elements, .. } => elements
+            .iter()
+            .map(annotation_to_string)
+            .collect::<Vec<String>>()
+            .join(", "),
+        ExpressionType::Binop {
+            a,
+            op: Operator::BitOr,
+            b,
+            ..
+        } => format!("{} | {}", annotation_to_string(a), annotation_to_string(b)),
+        ExpressionType::String {
+            value: StringGroup::Constant { value },
+            ..
+        } => format!("{:?}", value),
+        ExpressionType::Number {
+            value: Number::Integer { value },
+        } => value.to_string(),
+        ExpressionType::None => "None".into(),
+        _ => "...".into(),
+    }
+}
+
+fn unsupported_annotation(annotation: &Located<ExpressionType>) -> Diagnostic {
+    Diagnostic::at(
+        &annotation.location,
+        format!(
+            "annotation `{}` is not supported",
+            annotation_to_string(annotation)
+        ),
+    )
+}
+
+/// Returns the name of a type annotation, `typing.` and `py_apify.` prefixes
+/// excluded.
+fn annotation_name(annotation: &Located<ExpressionType>) -> Option<&str> {
+    match &annotation.node {
+        ExpressionType::Identifier { name, .. } => Some(name.as_str()),
+        ExpressionType::Attribute { value, name, .. } => match &value.node {
+            ExpressionType::Identifier { name: module, .. }
+                if module == "typing" || module == "py_apify" =>
+            {
+                Some(name.as_str())
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Values of `Literal["a", "b"]`, which must all be strings.
+fn literal_values(
+    annotation: &Located<ExpressionType>,
+    values: &Located<ExpressionType>,
+) -> Result<Vec<String>, Diagnostic> {
+    let elements = match &values.node {
+        ExpressionType::Tuple { elements, .. } => elements.iter().collect(),
+        _ => vec![values],
+    };
+
+    elements
+        .into_iter()
+        .map(|element| match &element.node {
+            ExpressionType::String {
+                value: StringGroup::Constant { value },
+            } => Ok(value.clone()),
+            _ => Err(unsupported_annotation(annotation)),
+        })
+        .collect()
+}
+
+fn parse_data_type(
+    annotation: &Located<ExpressionType>,
+    classes: &PyClasses,
+) -> Result<PyPrimitiveDataType, Diagnostic> {
+    match &annotation.node {
+        ExpressionType::Subscript { a, b, .. } => match annotation_name(a) {
+            Some("list") | Some("List") | Some("Sequence") => match parse_data_type(b, classes)? {
+                item_data_type if item_data_type.is_upload() => {
+                    Err(unsupported_annotation(annotation))
+                }
+                item_data_type => Ok(PyPrimitiveDataType::List(Box::new(item_data_type))),
+            },
+            Some("Literal") => Ok(PyPrimitiveDataType::Enum(PyEnum {
+                ident: String::new(),
+                class_name: None,
+                values: literal_values(annotation, b)?,
+            })),
+            _ => Err(unsupported_annotation(annotation)),
+        },
+        ExpressionType::Identifier { name } if classes.enums.contains_key(name) => {
+            Ok(PyPrimitiveDataType::Enum(PyEnum {
+                ident: String::new(),
+                class_name: Some(name.clone()),
+                values: classes.enums[name].clone(),
+            }))
+        }
+        ExpressionType::Identifier { name } if classes.structs.contains_key(name) => Ok(
+            PyPrimitiveDataType::Struct(classes.parse_struct(annotation, name)?),
+        ),
+        _ => annotation_name(annotation)
+            .and_then(PyPrimitiveDataType::from_name)
+            .ok_or_else(|| unsupported_annotation(annotation)),
+    }
+}
+
+fn is_none(expression: &Located<ExpressionType>) -> bool {
+    matches!(expression.node, ExpressionType::None)
+}
+
+/// Constraint declared in the metadata of an `Annotated[T, ...]` annotation,
+/// with the classes of `py_apify.py`. It is checked before `call` runs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PyConstraint {
+    /// `Range(min, max)`: inclusive bounds of a number, each one optional
+    Range { min: Option<f64>, max: Option<f64> },
+    /// `MinLen(length)`: minimum length of a string or a list
+    MinLen(u64),
+    /// `MaxLen(length)`: maximum length of a string or a list
+    MaxLen(u64),
+}
+
+impl PyConstraint {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PyConstraint::Range { .. } => "Range",
+            PyConstraint::MinLen(_) => "MinLen",
+            PyConstraint::MaxLen(_) => "MaxLen",
+        }
+    }
+
+    fn applies_to(&self, data_type: &PyPrimitiveDataType) -> bool {
+        matches!(
+            (self, data_type),
+            (
+                PyConstraint::Range { .. },
+                PyPrimitiveDataType::Int | PyPrimitiveDataType::Float
+            ) | (
+                PyConstraint::MinLen(_) | PyConstraint::MaxLen(_),
+                PyPrimitiveDataType::Str | PyPrimitiveDataType::List(_)
+            )
+        )
+    }
+
+    /// Parses an item of `Annotated` metadata. Items other than `Range`,
+    /// `MinLen` and `MaxLen` calls are ignored.
+    fn parse(metadata: &Located<ExpressionType>) -> Result<Option<PyConstraint>, Diagnostic> {
+        let (function, args, keywords) = match &metadata.node {
+            ExpressionType::Call {
+                function,
+                args,
+                keywords,
+            } => (function, args, keywords),
+            _ => return Ok(None),
+        };
+
+        let (name, params): (&str, &[&str]) = match annotation_name(function) {
+            Some("Range") => ("Range", &["min", "max"]),
+            Some("MinLen") => ("MinLen", &["length"]),
+            Some("MaxLen") => ("MaxLen", &["length"]),
+            _ => return Ok(None),
+        };
+
+        let invalid = || {
+            Diagnostic::at(
+                &metadata.location,
+                format!("the arguments of `{}` must be number literals", name),
+            )
+        };
+
+        let named_args = args
+            .iter()
+            .enumerate()
+            .map(|(i, arg)| params.get(i).map(|param| (*param, arg)))
+            .chain(keywords.iter().map(|keyword| {
+                params
+                    .iter()
+                    .find(|param| keyword.name.as_deref() == Some(**param))
+                    .map(|param| (*param, &keyword.value))
+            }));
+
+        let mut values: HashMap<&str, f64> = HashMap::new();
+
+        for named_arg in named_args {
+            let (param, arg) = named_arg.ok_or_else(invalid)?;
+
+            match PyDefault::from(arg) {
+                PyDefault::Int(value) => values.insert(param, value as f64),
+                PyDefault::Float(value) => values.insert(param, value),
+                PyDefault::None => None,
+                _ => return Err(invalid()),
+            };
+        }
+
+        let length = || match values.get("length") {
+            Some(length) if *length >= 0.0 && length.fract() == 0.0 => Ok(*length as u64),
+            _ => Err(invalid()),
+        };
+
+        Ok(Some(match name {
+            "Range" => PyConstraint::Range {
+                min: values.get("min").copied(),
+                max: values.get("max").copied(),
+            },
+            "MinLen" => PyConstraint::MinLen(length()?),
+            _ => PyConstraint::MaxLen(length()?),
+        }))
+    }
+
+    /// Adds the constraint to the JSON schema of a parameter.
+    fn extend_json_schema(&self, schema: &mut Value) {
+        let is_array = schema["type"] == "array";
+
+        match self {
+            PyConstraint::Range { min, max } => {
+                if let Some(min) = min {
+                    schema["minimum"] = json!(min);
+                }
+                if let Some(max) = max {
+                    schema["maximum"] = json!(max);
+                }
+            }
+            PyConstraint::MinLen(length) if is_array => schema["minItems"] = json!(length),
+            PyConstraint::MinLen(length) => schema["minLength"] = json!(length),
+            PyConstraint::MaxLen(length) if is_array => schema["maxItems"] = json!(length),
+            PyConstraint::MaxLen(length) => schema["maxLength"] = json!(length),
+        }
+    }
+}
+
+/// Parses a type annotation, unwrapping `Optional[T]`, `Union[T, None]`,
+/// `T | None` and `Annotated[T, ...]`. The returned flag is `true` when the
+/// annotation accepts `None`, the constraints come from `Annotated`.
+pub fn parse_annotation(
+    annotation: &Located<ExpressionType>,
+    classes: &PyClasses,
+) -> Result<(PyPrimitiveDataType, bool, Vec<PyConstraint>), Diagnostic> {
+    match &annotation.node {
+        ExpressionType::Subscript { a, b, .. } => match (annotation_name(a), &b.node) {
+            (Some("Optional"), _) => parse_annotation(b, classes)
+                .map(|(data_type, _, constraints)| (data_type, true, constraints)),
+            (Some("Union"), ExpressionType::Tuple { elements, .. }) => {
+                let not_none: Vec<&Located<ExpressionType>> =
+                    elements.iter().filter(|e| !is_none(e)).collect();
+
+                match not_none.as_slice() {
+                    [data_type] => parse_annotation(data_type, classes).map(
+                        |(data_type, nullable, constraints)| {
+                            (
+                                data_type,
+                                nullable || not_none.len() < elements.len(),
+                                constraints,
+                            )
+                        },
+                    ),
+                    _ => Err(unsupported_annotation(annotation)),
+                }
+            }
+            (Some("Annotated"), ExpressionType::Tuple { elements, .. }) => {
+                let (annotated, metadata) = elements
+                    .split_first()
+                    .ok_or_else(|| unsupported_annotation(annotation))?;
+                let (data_type, nullable, mut constraints) = parse_annotation(annotated, classes)?;
+
+                for item in metadata {
+                    match PyConstraint::parse(item)? {
+                        Some(constraint) if !constraint.applies_to(&data_type) => {
+                            return Err(Diagnostic::at(
+                                &item.location,
+                                format!(
+                                    "`{}` can't constrain a `{}` parameter",
+                                    constraint.name(),
+                                    data_type.python_name()
+                                ),
+                            ))
+                        }
+                        Some(constraint) => constraints.push(constraint),
+                        None => {}
+                    }
+                }
+
+                Ok((data_type, nullable, constraints))
+            }
+            _ => Ok((parse_data_type(annotation, classes)?, false, vec![])),
+        },
+        ExpressionType::Binop {
+            a,
+            op: Operator::BitOr,
+            b,
+            ..
+        } if is_none(b) => parse_annotation(a, classes)
+            .map(|(data_type, _, constraints)| (data_type, true, constraints)),
+        ExpressionType::Binop {
+            a,
+            op: Operator::BitOr,
+            b,
+            ..
+        } if is_none(a) => parse_annotation(b, classes)
+            .map(|(data_type, _, constraints)| (data_type, true, constraints)),
+        _ => Ok((parse_data_type(annotation, classes)?, false, vec![])),
+    }
+}
+
+impl From<PyPrimitiveDataType> for TokenStream2 {
+    fn from(py_primitive_data_type: PyPrimitiveDataType) -> Self {
+        match py_primitive_data_type {
+            PyPrimitiveDataType::Str => quote! { String },
+            PyPrimitiveDataType::Float => quote! { f64 },
+            PyPrimitiveDataType::Int => quote! { i64 },
+            PyPrimitiveDataType::Bool => quote! { bool },
+            PyPrimitiveDataType::List(item_data_type) => {
+                let item_data_type: TokenStream2 = (*item_data_type).into();
+                quote! { Vec<#item_data_type> }
+            }
+            PyPrimitiveDataType::Bytes => quote! { UploadBytes },
+            PyPrimitiveDataType::UploadFile => quote! { UploadFile },
+            PyPrimitiveDataType::Enum(py_enum) => {
+                let ident = Ident::new(&py_enum.ident, Span::call_site());
+                quote! { #ident }
+            }
+            PyPrimitiveDataType::Struct(py_struct) => {
+                let ident = Ident::new(&py_struct.ident, Span::call_site());
+                quote! { #ident }
+            }
+        }
+    }
+}
+
+impl PyPrimitiveDataType {
+    pub fn json_schema(&self) -> Value {
+        match self {
+            PyPrimitiveDataType::Str => json!({ "type": "string" }),
+            PyPrimitiveDataType::Float => json!({ "type": "number", "format": "double" }),
+            PyPrimitiveDataType::Int => json!({ "type": "integer", "format": "int64" }),
+            PyPrimitiveDataType::Bool => json!({ "type": "boolean" }),
+            PyPrimitiveDataType::List(item_data_type) => {
+                json!({ "type": "array", "items": item_data_type.json_schema() })
+            }
+            PyPrimitiveDataType::Bytes | PyPrimitiveDataType::UploadFile => {
+                json!({ "type": "string", "format": "binary" })
+            }
+            PyPrimitiveDataType::Enum(py_enum) => {
+                let mut schema = json!({ "type": "string", "enum": py_enum.values });
+
+                if let Some(class_name) = &py_enum.class_name {
+                    schema["title"] = json!(class_name);
+                }
+
+                schema
+            }
+            PyPrimitiveDataType::Struct(py_struct) => {
+                let properties: Map<String, Value> = py_struct
+                    .fields
+                    .iter()
+                    .map(|field| (field.name.clone(), field.json_schema()))
+                    .collect();
+
+                let required: Vec<&str> = py_struct
+                    .fields
+                    .iter()
+                    .filter(|field| !field.optional)
+                    .map(|field| field.name.as_ref())
+                    .collect();
+
+                json!({
+                    "type": "object",
+                    "title": py_struct.class_name,
+                    "properties": properties,
+                    "required": required,
+                    "additionalProperties": false,
+                })
+            }
+        }
+    }
+}
+
+/// Default value of a `call` parameter, as written in the Python source.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PyDefault {
+    None,
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    List(Vec<PyDefault>),
+    /// Any default that is not a literal (a constant, a function call...). It
+    /// can only be evaluated by Python.
+    Expression,
+}
+
+impl From<&Number> for PyDefault {
+    fn from(number: &Number) -> PyDefault {
+        match number {
+            Number::Integer { value } => value
+                .to_string()
+                .parse()
+                .map(PyDefault::Int)
+                .unwrap_or(PyDefault::Expression),
+            Number::Float { value } => PyDefault::Float(*value),
+            _ => PyDefault::Expression,
+        }
+    }
+}
+
+impl From<&Located<ExpressionType>> for PyDefault {
+    fn from(expression: &Located<ExpressionType>) -> PyDefault {
+        match &expression.node {
+            ExpressionType::None => PyDefault::None,
+            ExpressionType::True => PyDefault::Bool(true),
+            ExpressionType::False => PyDefault::Bool(false),
+            ExpressionType::String {
+                value: StringGroup::Constant { value },
+                ..
+            } => PyDefault::Str(value.clone()),
+            ExpressionType::Number { value, .. } => PyDefault::from(value),
+            ExpressionType::Unop {
+                op: UnaryOperator::Neg,
+                a,
+                ..
+            } => match PyDefault::from(a.as_ref()) {
+                PyDefault::Int(value) => PyDefault::Int(-value),
+                PyDefault::Float(value) => PyDefault::Float(-value),
+                _ => PyDefault::Expression,
+            },
+            ExpressionType::List { elements, .. } | ExpressionType::Tuple { elements, .. } => {
+                let items: Vec<PyDefault> = elements.iter().map(PyDefault::from).collect();
+
+                if items
+                    .iter()
+                    .any(|item| matches!(item, PyDefault::None | PyDefault::Expression))
+                {
+                    PyDefault::Expression
+                } else {
+                    PyDefault::List(items)
+                }
+            }
+            _ => PyDefault::Expression,
+        }
+    }
+}
+
+fn negate_if(negative: bool, literal: TokenStream2) -> TokenStream2 {
+    if negative {
+        quote! { -#literal }
+    } else {
+        literal
+    }
+}
+
+impl PyDefault {
+    /// Builds the Rust expression of this default for a field of type `data_type`.
+    /// Returns `None` when the default can't be expressed on the Rust side.
+    pub fn to_tokens(&self, data_type: &PyPrimitiveDataType) -> Option<TokenStream2> {
+        match (self, data_type) {
+            (PyDefault::Str(value), PyPrimitiveDataType::Str) => {
+                let value = Literal::string(value);
+                Some(quote! { String::from(#value) })
+            }
+            (PyDefault::Int(value), PyPrimitiveDataType::Int) => {
+                let literal = Literal::u64_unsuffixed(value.unsigned_abs());
+                Some(negate_if(*value < 0, quote! { #literal }))
+            }
+            (PyDefault::Int(value), PyPrimitiveDataType::Float) => {
+                let literal = Literal::f64_unsuffixed(value.unsigned_abs() as f64);
+                Some(negate_if(*value < 0, quote! { #literal }))
+            }
+            (PyDefault::Float(value), PyPrimitiveDataType::Float) if value.is_finite() => {
+                let literal = Literal::f64_unsuffixed(value.abs());
+                Some(negate_if(value.is_sign_negative(), quote! { #literal }))
+            }
+            (PyDefault::Bool(value), PyPrimitiveDataType::Bool) => Some(quote! { #value }),
+            (PyDefault::Str(value), PyPrimitiveDataType::Enum(py_enum)) => {
+                let ident = Ident::new(&py_enum.ident, Span::call_site());
+                let variant = py_enum.variant_ident(value)?;
+                Some(quote! { #ident::#variant })
+            }
+            (PyDefault::List(items), PyPrimitiveDataType::List(item_data_type)) => items
+                .iter()
+                .map(|item| item.to_tokens(item_data_type))
+                .collect::<Option<Vec<TokenStream2>>>()
+                .map(|items| quote! { vec![#(#items),*] }),
+            _ => None,
+        }
+    }
+
+    pub fn to_json(&self) -> Option<Value> {
+        match self {
+            PyDefault::Str(value) => Some(json!(value)),
+            PyDefault::Int(value) => Some(json!(value)),
+            PyDefault::Float(value) => Some(json!(value)),
+            PyDefault::Bool(value) => Some(json!(value)),
+            PyDefault::List(items) => items.iter().map(PyDefault::to_json).collect(),
+            PyDefault::None | PyDefault::Expression => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyArg {
+    pub name: String,
+    pub data_type: PyPrimitiveDataType,
+    pub optional: bool,
+    pub default: Option<PyDefault>,
+    pub constraints: Vec<PyConstraint>,
+    /// Description from the `Args:` section of the docstring of `call`
+    pub doc: Option<String>,
+}
+
+impl PyArg {
+    /// Names the Rust enums and structs of the parameter after the file, the
+    /// types of every file being generated side by side: `Enum_{file}__{param}`
+    /// for a `Literal` or `Enum` and `Struct_{file}__{class}` for a `TypedDict`
+    /// or dataclass, whose enum fields become `Enum_{file}__{class}__{field}`.
+    /// Names can still collide when the Python names contain `__`, see
+    /// `check_ident_collisions`.
+    pub fn scope_idents(&mut self, file_ident: &str) {
+        self.data_type.scope(file_ident, &self.name);
+    }
+
+    /// Rust expression of the default value, if it has one.
+    pub fn default_tokens(&self) -> Option<TokenStream2> {
+        self.default
+            .as_ref()
+            .and_then(|default| default.to_tokens(&self.data_type))
+    }
+
+    /// Schema of the parameter. Optional parameters are `nullable`: `null`
+    /// is received like a missing value.
+    pub fn json_schema(&self) -> Value {
+        let mut schema = self.data_type.json_schema();
+
+        if self.optional {
+            schema["nullable"] = json!(true);
+        }
+
+        if let Some(default) = self.default.as_ref().and_then(PyDefault::to_json) {
+            schema["default"] = default;
+        }
+
+        for constraint in &self.constraints {
+            constraint.extend_json_schema(&mut schema);
+        }
+
+        if let Some(doc) = &self.doc {
+            schema["description"] = json!(doc);
+        }
+
+        schema
+    }
+}
+
+impl From<PyArg> for Ident {
+    fn from(py_arg: PyArg) -> Self {
+        Ident::new(&py_arg.name, Span::call_site())
+    }
+}
+
+impl From<PyArg> for TokenStream2 {
+    fn from(py_arg: PyArg) -> Self {
+        let struct_field_ident: Ident = py_arg.clone().into();
+        let data_type_tokens: TokenStream2 = py_arg.data_type.into();
+
+        // `PyOptional` is generated by `apify!`, unlike `Option` it rejects
+        // values that don't parse
+        let data_type = if py_arg.optional {
+            quote! {
+                PyOptional<#data_type_tokens>
+            }
+        } else {
+            quote! {
+                #data_type_tokens
+            }
+        };
+
+        quote! {
+            #struct_field_ident: #data_type
+        }
+    }
+}
+
+pub fn get_func_by_name<'a>(
+    program: &'a Program,
+    func_name: &'a str,
+) -> Option<&'a Located<StatementType>> {
+    program.statements.iter().find(|statement| {
+        if let StatementType::FunctionDef { name, .. } = &statement.node {
+            return name == func_name;
+        }
+
+        false
+    })
+}
+
+/// Collects the parameters of a function along with their default value.
+pub fn collect_func_args(
+    func: &Located<StatementType>,
+) -> Vec<(&Parameter, Option<&Located<ExpressionType>>)> {
+    let mut func_args = vec![];
+
+    if let StatementType::FunctionDef { args, .. } = &func.node {
+        // positional defaults belong to the last positional parameters
+        let first_default = args.args.len().saturating_sub(args.defaults.len());
+
+        func_args.extend(args.args.iter().enumerate().map(|(i, arg)| {
+            let default = i
+                .checked_sub(first_default)
+                .and_then(|i| args.defaults.get(i));
+            (arg, default)
+        }));
+
+        if let Varargs::Named(param) = &args.vararg {
+            func_args.push((param, None));
+        };
+
+        func_args.extend(
+            args.kwonlyargs
+                .iter()
+                .zip(args.kw_defaults.iter())
+                .map(|(arg, default)| (arg, default.as_ref())),
+        );
+    }
+
+    func_args
+}
+
+pub fn get_func_args(py_code: String, func_name: &str) -> Result<Vec<PyArg>, Diagnostic> {
+    let program = parser::parse_program(&py_code)
+        .map_err(|e| Diagnostic::at(&e.location, e.error.to_string()))?;
+
+    let classes = PyClasses::from(&program);
+
+    let call_func = get_func_by_name(&program, func_name).ok_or_else(|| {
+        Diagnostic::new(format!(
+            "`{}` function not found. Your python file must contain a `{}` function",
+            func_name, func_name
+        ))
+    })?;
+
+    collect_func_args(call_func)
+        .into_iter()
+        .map(|(arg, default)| {
+            let (data_type, nullable, constraints) = match &arg.annotation {
+                Some(annotation) => parse_annotation(annotation, &classes)?,
+                None => (PyPrimitiveDataType::Str, false, vec![]),
+            };
+
+            let default = default.map(PyDefault::from);
+
+            Ok(PyArg {
+                name: arg.arg.to_string(),
+                data_type,
+                optional: nullable || default.is_some(),
+                default,
+                constraints,
+                doc: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_annotations() {
+        let py_code = "def call(inputs: list[str], scores: typing.List[float], ids: List[int] = []):\n    pass\n";
+
+        let args = get_func_args(py_code.to_string(), "call").unwrap();
+        let data_types: Vec<String> = args
+            .into_iter()
+            .map(|arg| {
+                let data_type: TokenStream2 = arg.data_type.into();
+                data_type.to_string()
+            })
+            .collect();
+
+        assert_eq!(
+            data_types,
+            vec![
+                quote! { Vec<String> }.to_string(),
+                quote! { Vec<f64> }.to_string(),
+                quote! { Vec<i64> }.to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_optional_annotations_and_defaults() {
+        let py_code = "def call(input, a: Optional[int], b: int | None = None, c: typing.Union[None, float] = 0.5, *, top_k: int = 5, mode: str = 'fast', ids: list[int] = [1, 2]):\n    pass\n";
+
+        let args = get_func_args(py_code.to_string(), "call").unwrap();
+        let summary: Vec<(&str, bool, Option<PyDefault>)> = args
+            .iter()
+            .map(|arg| (arg.name.as_ref(), arg.optional, arg.default.clone()))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("input", false, None),
+                ("a", true, None),
+                ("b", true, Some(PyDefault::None)),
+                ("c", true, Some(PyDefault::Float(0.5))),
+                ("top_k", true, Some(PyDefault::Int(5))),
+                ("mode", true, Some(PyDefault::Str("fast".into()))),
+                (
+                    "ids",
+                    true,
+                    Some(PyDefault::List(vec![PyDefault::Int(1), PyDefault::Int(2)]))
+                ),
+            ]
+        );
+
+        assert_eq!(
+            args[3].default_tokens().unwrap().to_string(),
+            quote! { 0.5 }.to_string()
+        );
+        assert_eq!(
+            args[5].json_schema(),
+            json!({ "type": "string", "default": "fast", "nullable": true })
+        );
+    }
+
+    #[test]
+    fn test_upload_annotations() {
+        let py_code = "import py_apify\n\ndef call(audio: bytes, scan: py_apify.UploadFile, lang: str = 'fr'):\n    pass\n";
+
+        let args = get_func_args(py_code.to_string(), "call").unwrap();
+        let data_types: Vec<String> = args.iter().map(|arg| arg.data_type.python_name()).collect();
+
+        assert_eq!(data_types, vec!["bytes", "UploadFile", "str"]);
+        assert!(args[0].data_type.is_upload() && args[1].data_type.is_upload());
+        assert_eq!(
+            args[1].json_schema(),
+            json!({ "type": "string", "format": "binary" })
+        );
+
+        let error =
+            get_func_args("def call(pages: list[bytes]):\n    pass\n".into(), "call").unwrap_err();
+        assert_eq!(error.message, "annotation `list[bytes]` is not supported");
+    }
+
+    #[test]
+    fn test_enum_annotations() {
+        let py_code = "from enum import Enum\n\nclass Mode(str, Enum):\n    FAST = 'fast'\n    _ignore_ = []\n    ACCURATE = 'accurate'\n\ndef call(aggregation: Literal['simple', 'max'] = 'max', mode: Mode | None = None, labels: list[typing.Literal['PER']] = []):\n    pass\n";
+
+        let mut args = get_func_args(py_code.to_string(), "call").unwrap();
+        for arg in &mut args {
+            arg.scope_idents("ner");
+        }
+
+        assert_eq!(
+            args[0].data_type.py_enum(),
+            Some(&PyEnum {
+                ident: "Enum_ner__aggregation".into(),
+                class_name: None,
+                values: vec!["simple".into(), "max".into()],
+            })
+        );
+        assert_eq!(
+            args[0].data_type.python_name(),
+            "Literal[\"simple\", \"max\"]"
+        );
+        assert_eq!(
+            args[0].default_tokens().unwrap().to_string(),
+            quote! { Enum_ner__aggregation::V1 }.to_string()
+        );
+        assert_eq!(
+            args[1].json_schema(),
+            json!({ "type": "string", "enum": ["fast", "accurate"], "title": "Mode", "nullable": true })
+        );
+        assert!(args[1].optional);
+
+        let data_type: TokenStream2 = args[2].data_type.clone().into();
+        assert_eq!(
+            data_type.to_string(),
+            quote! { Vec<Enum_ner__labels> }.to_string()
+        );
+
+        let error = get_func_args("def call(top_k: Literal[1, 2]):\n    pass\n".into(), "call")
+            .unwrap_err();
+        assert_eq!(error.message, "annotation `Literal[1, 2]` is not supported");
+    }
+
+    #[test]
+    fn test_struct_annotations() {
+        let py_code = "from dataclasses import dataclass, field\n\nclass Span(TypedDict):\n    start: int\n    end: int\n    label: Literal['PER', 'LOC'] | None\n\n@dataclass(frozen=True)\nclass Document:\n    text: str\n    spans: list[Span]\n    lang: str = 'fr'\n    MAX_SPANS: ClassVar[int] = 100\n\nclass Options(TypedDict, total=False):\n    top_k: int\n\ndef call(doc: Document, options: Options | None = None):\n    pass\n";
+
+        let mut args = get_func_args(py_code.to_string(), "call").unwrap();
+        for arg in &mut args {
+            arg.scope_idents("linker");
+        }
+
+        let doc = match &args[0].data_type {
+            PyPrimitiveDataType::Struct(py_struct) => py_struct,
+            data_type => panic!("unexpected data type {:?}", data_type),
+        };
+        let fields: Vec<(&str, String, bool)> = doc
+            .fields
+            .iter()
+            .map(|field| {
+                let data_type: TokenStream2 = field.data_type.clone().into();
+                (field.name.as_ref(), data_type.to_string(), field.optional)
+            })
+            .collect();
+
+        assert_eq!(doc.ident, "Struct_linker__Document");
+        assert_eq!(doc.kind, PyStructKind::Dataclass);
+        assert_eq!(
+            fields,
+            vec![
+                ("text", quote! { String }.to_string(), false),
+                (
+                    "spans",
+                    quote! { Vec<Struct_linker__Document__Span> }.to_string(),
+                    false
+                ),
+                ("lang", quote! { String }.to_string(), true),
+            ]
+        );
+        assert_eq!(
+            args[0].json_schema()["properties"]["spans"]["items"],
+            json!({
+                "type": "object",
+                "title": "Span",
+                "properties": {
+                    "start": { "type": "integer", "format": "int64" },
+                    "end": { "type": "integer", "format": "int64" },
+                    "label": { "type": "string", "enum": ["PER", "LOC"], "nullable": true },
+                },
+                "required": ["start", "end"],
+                "additionalProperties": false,
+            })
+        );
+        assert_eq!(args[0].json_schema()["required"], json!(["text", "spans"]));
+        match &doc.fields[1].data_type {
+            PyPrimitiveDataType::List(span) => match span.as_ref() {
+                PyPrimitiveDataType::Struct(span) => assert_eq!(
+                    span.fields[2].data_type.py_enum().unwrap().ident,
+                    "Enum_linker__Document__Span__label"
+                ),
+                data_type => panic!("unexpected data type {:?}", data_type),
+            },
+            data_type => panic!("unexpected data type {:?}", data_type),
+        }
+
+        let options = match &args[1].data_type {
+            PyPrimitiveDataType::Struct(py_struct) => py_struct,
+            data_type => panic!("unexpected data type {:?}", data_type),
+        };
+        assert_eq!(options.kind, PyStructKind::TypedDict);
+        assert_eq!(options.fields[0].default, Some(PyDefault::Expression));
+        assert!(args[1].optional);
+    }
+
+    #[test]
+    fn test_invalid_structs() {
+        let error = get_func_args(
+            "class Node(TypedDict):\n    children: list[Node]\n\ndef call(tree: Node):\n    pass\n"
+                .into(),
+            "call",
+        )
+        .unwrap_err();
+        assert_eq!(error.message, "`Node` can't contain itself");
+        assert_eq!(error.location.map(|(row, _)| row), Some(2));
+
+        let error = get_func_args(
+            "class Span(TypedDict):\n    start: Annotated[int, Range(0)]\n\ndef call(span: Span):\n    pass\n"
+                .into(),
+            "call",
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.message,
+            "`Annotated` constraints are not supported in the fields of `Span`"
+        );
+    }
+
+    #[test]
+    fn test_annotated_constraints() {
+        let py_code = "def call(text: Annotated[str, MaxLen(10000), 'the text'], top_k: Optional[typing.Annotated[int, py_apify.Range(1, max=50)]] = 5, threshold: Annotated[float, Range(min=-1.5)] = 0.5, ids: Annotated[list[int], MinLen(1)] = [1]):\n    pass\n";
+
+        let args = get_func_args(py_code.to_string(), "call").unwrap();
+        let constraints: Vec<&[PyConstraint]> =
+            args.iter().map(|arg| arg.constraints.as_slice()).collect();
+
+        assert_eq!(
+            constraints,
+            vec![
+                &[PyConstraint::MaxLen(10000)][..],
+                &[PyConstraint::Range {
+                    min: Some(1.0),
+                    max: Some(50.0)
+                }],
+                &[PyConstraint::Range {
+                    min: Some(-1.5),
+                    max: None
+                }],
+                &[PyConstraint::MinLen(1)],
+            ]
+        );
+        assert!(args[1].optional);
+        assert_eq!(
+            args[0].json_schema(),
+            json!({ "type": "string", "maxLength": 10000 })
+        );
+        assert_eq!(
+            args[1].json_schema(),
+            json!({ "type": "integer", "format": "int64", "default": 5, "minimum": 1.0, "maximum": 50.0, "nullable": true })
+        );
+        assert_eq!(
+            args[3].json_schema(),
+            json!({ "type": "array", "items": { "type": "integer", "format": "int64" }, "default": [1], "minItems": 1, "nullable": true })
+        );
+    }
+
+    #[test]
+    fn test_invalid_constraints() {
+        let error = get_func_args(
+            "def call(text: Annotated[str, Range(1, 5)]):\n    pass\n".into(),
+            "call",
+        )
+        .unwrap_err();
+        assert_eq!(error.message, "`Range` can't constrain a `str` parameter");
+
+        let error = get_func_args(
+            "def call(text: Annotated[str, MaxLen(LIMIT)]):\n    pass\n".into(),
+            "call",
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.message,
+            "the arguments of `MaxLen` must be number literals"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_annotation() {
+        let py_code = "import json\n\ndef call(input: str, options: dict):\n    pass\n";
+
+        let error = get_func_args(py_code.to_string(), "call").unwrap_err();
+
+        assert_eq!(error.message, "annotation `dict` is not supported");
+        assert_eq!(error.location.map(|(row, _)| row), Some(3));
+    }
+
+    #[test]
+    fn test_missing_call_function() {
+        let py_code = "def main(input):\n    pass\n";
+
+        let error = get_func_args(py_code.to_string(), "call").unwrap_err();
+
+        assert_eq!(error.location, None);
+        assert!(error.message.starts_with("`call` function not found"));
+    }
+}
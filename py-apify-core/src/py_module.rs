@@ -0,0 +1,194 @@
+//! The `py_apify` Python module, injected in the interpreter before the Python
+//! files are imported.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::pyfunction;
+use pyo3::types::PyModule;
+use pyo3::{wrap_pyfunction, PyErr, PyResult, Python};
+
+/// Name under which the module is importable from Python.
+pub const PY_APIFY_MODULE_NAME: &str = "py_apify";
+
+/// Source of the module.
+pub const PY_APIFY_MODULE: &str = include_str!("py_apify.py");
+
+/// Status of the responses to a `ValueError` raised by `call`.
+pub const VALUE_ERROR_STATUS: u16 = 422;
+
+/// How an exception raised by `call` is answered.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PyException {
+    /// `py_apify.HTTPError` with its own status, or `ValueError` answered
+    /// with `VALUE_ERROR_STATUS`
+    Rejected {
+        status: u16,
+        exception: String,
+        detail: String,
+    },
+    /// Any other exception, answered with a 500
+    Failure(String),
+}
+
+impl PyException {
+    /// Maps an exception raised by a hook. `py_apify_module` is the injected
+    /// `py_apify` module, `HTTPError` can't be recognized without it.
+    pub fn from_py_err(py: Python, py_apify_module: Option<&PyModule>, e: &PyErr) -> Self {
+        let exception = e.ptype(py).name().unwrap_or("Exception").to_string();
+        let value = e.pvalue(py);
+
+        let http_error_status = py_apify_module
+            .and_then(|module| module.getattr("HTTPError").ok())
+            .filter(|http_error| e.matches(py, *http_error))
+            .and_then(|_| {
+                value
+                    .getattr("status")
+                    .and_then(|status| status.extract::<u16>())
+                    .ok()
+            });
+
+        let status = match http_error_status {
+            Some(status) => status,
+            None if e.is_instance::<PyValueError>(py) => VALUE_ERROR_STATUS,
+            None => return PyException::Failure(e.to_string()),
+        };
+
+        let detail = match http_error_status {
+            Some(_) => value.getattr("detail").and_then(|detail| detail.str()),
+            None => value.str(),
+        }
+        .map(|detail| detail.to_string())
+        .unwrap_or_default();
+
+        PyException::Rejected {
+            status,
+            exception,
+            detail,
+        }
+    }
+}
+
+/// `log` level of a Python `logging` level.
+pub fn log_level(python_level: u32) -> log::Level {
+    match python_level {
+        level if level >= 40 => log::Level::Error,
+        level if level >= 30 => log::Level::Warn,
+        level if level >= 20 => log::Level::Info,
+        level if level >= 10 => log::Level::Debug,
+        _ => log::Level::Trace,
+    }
+}
+
+/// Python `logging` level matching a `log` filter, records below it are
+/// dropped before reaching Rust.
+pub fn python_level(filter: log::LevelFilter) -> u32 {
+    match filter {
+        log::LevelFilter::Off => 100,
+        log::LevelFilter::Error => 40,
+        log::LevelFilter::Warn => 30,
+        log::LevelFilter::Info => 20,
+        log::LevelFilter::Debug => 10,
+        log::LevelFilter::Trace => 1,
+    }
+}
+
+/// Logs a Python `logging` record or a line printed by Python, see `_install`
+/// in `py_apify.py`.
+#[pyfunction]
+fn py_apify_log(level: u32, target: &str, message: &str, request_id: Option<String>) {
+    let level = log_level(level);
+
+    match request_id {
+        Some(request_id) => log::log!(target: target, level, "[{}] {}", request_id, message),
+        None => log::log!(target: target, level, "{}", message),
+    }
+}
+
+/// Defines the `py_apify` module, so that the Python files can `import
+/// py_apify`. Its definition routes Python `logging` and
+/// `sys.stdout`/`sys.stderr` to the `log` crate.
+pub fn define_py_apify_module(py: Python<'_>) -> PyResult<&PyModule> {
+    let module = PyModule::from_code(
+        py,
+        PY_APIFY_MODULE,
+        &format!("{}.py", PY_APIFY_MODULE_NAME),
+        PY_APIFY_MODULE_NAME,
+    )?;
+
+    module.call_method1(
+        "_install",
+        (
+            wrap_pyfunction!(py_apify_log, py)?,
+            python_level(log::max_level()),
+        ),
+    )?;
+
+    Ok(module)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::IntoPyDict;
+
+    fn raise(code: &str) -> PyException {
+        pyo3::prepare_freethreaded_python();
+
+        Python::with_gil(|py| {
+            let module = PyModule::from_code(
+                py,
+                PY_APIFY_MODULE,
+                "py_apify.py",
+                PY_APIFY_MODULE_NAME,
+            )
+            .unwrap();
+            let locals = [("py_apify", module)].into_py_dict(py);
+            let e = py.run(code, None, Some(locals)).unwrap_err();
+
+            PyException::from_py_err(py, Some(module), &e)
+        })
+    }
+
+    #[test]
+    fn test_from_py_err() {
+        assert_eq!(
+            raise("raise py_apify.HTTPError(404, 'unknown entity')"),
+            PyException::Rejected {
+                status: 404,
+                exception: "HTTPError".to_string(),
+                detail: "unknown entity".to_string(),
+            }
+        );
+        assert_eq!(
+            raise("raise ValueError('top_k must be positive')"),
+            PyException::Rejected {
+                status: VALUE_ERROR_STATUS,
+                exception: "ValueError".to_string(),
+                detail: "top_k must be positive".to_string(),
+            }
+        );
+        assert_eq!(
+            raise("raise RuntimeError('model not loaded')"),
+            PyException::Failure("RuntimeError: model not loaded".to_string())
+        );
+    }
+
+    #[test]
+    fn test_levels() {
+        assert_eq!(log_level(50), log::Level::Error);
+        assert_eq!(log_level(30), log::Level::Warn);
+        assert_eq!(log_level(25), log::Level::Info);
+        assert_eq!(log_level(10), log::Level::Debug);
+        assert_eq!(log_level(5), log::Level::Trace);
+
+        for level in [
+            log::Level::Error,
+            log::Level::Warn,
+            log::Level::Info,
+            log::Level::Debug,
+            log::Level::Trace,
+        ] {
+            assert_eq!(log_level(python_level(level.to_level_filter())), level);
+        }
+        assert!(python_level(log::LevelFilter::Off) > 50);
+    }
+}
@@ -0,0 +1,440 @@
+use glob::{glob, Pattern};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use crate::check::check_py_code;
+use crate::diagnostic::Diagnostic;
+use crate::docstring::get_func_doc;
+use crate::py_arg::{get_func_args, PyArg, PyDefault, PyPrimitiveDataType};
+
+#[derive(Debug, Clone)]
+pub struct PythonFile {
+    pub path: PathBuf,
+    pub file_name: String,
+    pub file_stem: String,
+    /// Suffix of the generated identifiers, derived from the file stem
+    pub ident: String,
+    pub main_func_args: Vec<PyArg>,
+    /// Docstring of `call`, its sections excluded
+    pub doc: Option<String>,
+}
+
+/// Turns a file stem into an identifier suffix: every character that is not
+/// an ASCII letter, digit or underscore is replaced with an underscore.
+pub fn sanitize_ident(file_stem: &str) -> String {
+    file_stem
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+impl PythonFile {
+    /// Name of the Python module holding this file in the interpreter.
+    pub fn module_name(&self) -> String {
+        format!("py_apify_{}", self.ident)
+    }
+
+    /// `true` when `call` takes a `bytes` or `py_apify.UploadFile` parameter:
+    /// its route is then a multipart/form-data `POST`.
+    pub fn has_uploads(&self) -> bool {
+        self.main_func_args
+            .iter()
+            .any(|arg| arg.data_type.is_upload())
+    }
+
+    /// Describes the route of the file in the index document.
+    pub fn index_entry(&self) -> Value {
+        let methods = match self.has_uploads() {
+            true => json!(["POST"]),
+            false => json!(["GET", "POST"]),
+        };
+
+        let parameters: Vec<Value> = self
+            .main_func_args
+            .iter()
+            .map(|arg| {
+                json!({
+                    "name": arg.name,
+                    "type": arg.data_type.python_name(),
+                    "required": !arg.optional,
+                    "default": arg.default.as_ref().and_then(PyDefault::to_json),
+                    "description": arg.doc,
+                })
+            })
+            .collect();
+
+        json!({
+            "path": format!("/{}", self.file_stem),
+            "file": self.file_name,
+            "methods": methods,
+            "description": self.doc,
+            "parameters": parameters,
+        })
+    }
+
+    /// Absolute path of the file, as given to `include_str!`.
+    pub fn absolute_path(&self) -> String {
+        std::fs::canonicalize(&self.path)
+            .unwrap_or_else(|_| self.path.clone())
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// Reads the signature of the `call` function of a Python file. The
+    /// validation pass of `check.rs` runs first when `check` is `true`.
+    pub fn new(input: PathBuf, check: bool) -> Result<Self, Diagnostic> {
+        let file_name = input
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_else(|| input.display().to_string());
+
+        let py_code = read_to_string(&input).map_err(|e| {
+            Diagnostic::new(format!("failed to read file: {}", e)).in_file(&file_name)
+        })?;
+
+        if check {
+            check_py_code(&py_code, "call").map_err(|e| e.in_file(&file_name))?;
+        }
+
+        let file_stem = input
+            .file_stem()
+            .map(|file_stem| file_stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| file_name.clone());
+
+        let ident = sanitize_ident(&file_stem);
+
+        let docstring = get_func_doc(&py_code, "call").map_err(|e| e.in_file(&file_name))?;
+
+        let mut main_func_args =
+            get_func_args(py_code, "call").map_err(|e| e.in_file(&file_name))?;
+        for arg in &mut main_func_args {
+            arg.scope_idents(&ident);
+            arg.doc = docstring.args.get(&arg.name).cloned();
+        }
+
+        Ok(PythonFile {
+            ident,
+            file_stem,
+            main_func_args,
+            doc: docstring.description,
+            file_name,
+            path: input,
+        })
+    }
+}
+
+/// Lists the files matching `pattern`, relative patterns are resolved against
+/// `base_dir`. A pattern matching no file is most likely a typo.
+fn glob_files(base_dir: &Path, pattern: &str) -> Result<Vec<PathBuf>, Diagnostic> {
+    let full_pattern = match Path::new(pattern).is_absolute() {
+        true => pattern.to_string(),
+        false => format!(
+            "{}/{}",
+            Pattern::escape(&base_dir.to_string_lossy()),
+            pattern.trim_start_matches("./")
+        ),
+    };
+
+    let paths: Vec<PathBuf> = glob(&full_pattern)
+        .map_err(|e| Diagnostic::new(format!("invalid glob pattern `{}`: {}", pattern, e)))?
+        .filter_map(|e| e.ok())
+        .collect();
+
+    if paths.is_empty() {
+        return Err(Diagnostic::new(format!(
+            "`{}` doesn't match any file in {}",
+            pattern,
+            base_dir.display()
+        )));
+    }
+
+    Ok(paths)
+}
+
+/// Reads the Python files matching the `input` patterns, `./src/*.py` when
+/// there are none. The `apify!` macro resolves them against the directory of
+/// the crate being compiled, `py-apify` against the working directory.
+pub fn get_py_files(
+    input: Vec<String>,
+    base_dir: &Path,
+    check: bool,
+) -> Result<Vec<PythonFile>, Diagnostic> {
+    let mut files_name: Vec<PathBuf> = if !input.is_empty() {
+        input
+            .iter()
+            .map(|elem| glob_files(base_dir, elem))
+            .collect::<Result<Vec<Vec<PathBuf>>, Diagnostic>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<PathBuf>>()
+    } else {
+        glob_files(base_dir, "./src/*.py")?
+    };
+
+    files_name.sort();
+    files_name.dedup();
+
+    let python_files = files_name
+        .into_iter()
+        .map(|file_name| PythonFile::new(file_name, check))
+        .collect::<Result<Vec<PythonFile>, Diagnostic>>()?;
+
+    check_ident_collisions(&python_files)?;
+
+    Ok(python_files)
+}
+
+/// Lists the routes of the Python files with their documentation, served on `/`.
+pub fn index_document(python_files: &[PythonFile]) -> Value {
+    let endpoints: Vec<Value> = python_files.iter().map(PythonFile::index_entry).collect();
+
+    json!({ "endpoints": endpoints })
+}
+
+/// Two files whose stems sanitize to the same identifier would generate the
+/// same routes and functions, two different enums or structs named alike
+/// would be defined twice. A class used by several parameters of a file is
+/// generated once.
+fn check_ident_collisions(python_files: &[PythonFile]) -> Result<(), Diagnostic> {
+    for (i, python_file) in python_files.iter().enumerate() {
+        if let Some(other) = python_files[..i]
+            .iter()
+            .find(|other| other.ident == python_file.ident)
+        {
+            return Err(Diagnostic::new(format!(
+                "`{}` and `{}` both generate the identifier `{}`, please rename one of them",
+                other.path.display(),
+                python_file.path.display(),
+                python_file.ident
+            )));
+        }
+    }
+
+    let mut named_types: HashMap<&str, (&PythonFile, &PyPrimitiveDataType)> = HashMap::new();
+
+    for python_file in python_files {
+        let mut types = vec![];
+        for arg in &python_file.main_func_args {
+            arg.data_type.named_types(&mut types);
+        }
+
+        for data_type in types {
+            let ident = match data_type.named_type_ident() {
+                Some(ident) => ident,
+                None => continue,
+            };
+
+            match named_types.get(ident) {
+                Some((other_file, other_type))
+                    if other_file.path == python_file.path && *other_type == data_type => {}
+                Some((other_file, other_type)) => {
+                    return Err(Diagnostic::new(format!(
+                        "`{}` in `{}` and `{}` in `{}` both generate the type `{}`, please rename a parameter or a class",
+                        other_type.python_name(),
+                        other_file.path.display(),
+                        data_type.python_name(),
+                        python_file.path.display(),
+                        ident
+                    )));
+                }
+                None => {
+                    named_types.insert(ident, (python_file, data_type));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::py_arg::PyPrimitiveDataType;
+
+    fn python_file(path: &str) -> PythonFile {
+        let path = PathBuf::from(path);
+        let file_stem = path.file_stem().unwrap().to_str().unwrap().to_string();
+
+        PythonFile {
+            file_name: path.file_name().unwrap().to_str().unwrap().to_string(),
+            ident: sanitize_ident(&file_stem),
+            file_stem,
+            main_func_args: vec![],
+            doc: None,
+            path,
+        }
+    }
+
+    #[test]
+    fn test_glob_files() {
+        let base_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+        assert_eq!(
+            glob_files(base_dir, "./src/*.py").unwrap(),
+            vec![base_dir.join("src/py_apify.py")]
+        );
+
+        let e = get_py_files(vec!["src/*.pyi".into()], base_dir, true).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            format!(
+                "`src/*.pyi` doesn't match any file in {}",
+                base_dir.display()
+            )
+        );
+    }
+
+    #[test]
+    fn test_sanitize_ident() {
+        assert_eq!(sanitize_ident("jb-ner-dates"), "jb_ner_dates");
+        assert_eq!(sanitize_ident("camembert.masked lm"), "camembert_masked_lm");
+        assert_eq!(sanitize_ident("été"), "_t_");
+    }
+
+    #[test]
+    fn test_index_document() {
+        let mut ner = python_file("src/ner.py");
+        ner.doc = Some("Finds the named entities of a text.".into());
+        ner.main_func_args = vec![PyArg {
+            name: "top_k".into(),
+            data_type: PyPrimitiveDataType::Int,
+            optional: true,
+            default: Some(PyDefault::Int(5)),
+            constraints: vec![],
+            doc: Some("Maximum number of entities.".into()),
+        }];
+
+        assert_eq!(
+            index_document(&[ner]),
+            json!({
+                "endpoints": [{
+                    "path": "/ner",
+                    "file": "ner.py",
+                    "methods": ["GET", "POST"],
+                    "description": "Finds the named entities of a text.",
+                    "parameters": [{
+                        "name": "top_k",
+                        "type": "int",
+                        "required": false,
+                        "default": 5,
+                        "description": "Maximum number of entities.",
+                    }],
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_ident_collisions() {
+        assert_eq!(
+            check_ident_collisions(&[python_file("src/a-b.py"), python_file("src/c.py")]),
+            Ok(())
+        );
+
+        let error = check_ident_collisions(&[python_file("src/a-b.py"), python_file("src/a_b.py")])
+            .unwrap_err();
+
+        assert_eq!(
+            error.message,
+            "`src/a-b.py` and `src/a_b.py` both generate the identifier `a_b`, please rename one of them"
+        );
+    }
+
+    fn python_file_with_args(path: &str, py_code: &str) -> PythonFile {
+        let mut python_file = python_file(path);
+        python_file.main_func_args = get_func_args(py_code.to_string(), "call").unwrap();
+
+        for arg in &mut python_file.main_func_args {
+            arg.scope_idents(&python_file.ident);
+        }
+
+        python_file
+    }
+
+    #[test]
+    fn test_type_ident_collisions() {
+        // `a_b` + `c` and `a` + `b_c` are told apart by the separator
+        assert_eq!(
+            check_ident_collisions(&[
+                python_file_with_args("src/a_b.py", "def call(c: Literal['x']):\n    pass\n"),
+                python_file_with_args("src/a.py", "def call(b_c: Literal['y']):\n    pass\n"),
+            ]),
+            Ok(())
+        );
+
+        // a class used by two parameters of a file is generated once
+        let span = "class Span(TypedDict):\n    start: int\n\n";
+        assert_eq!(
+            check_ident_collisions(&[python_file_with_args(
+                "src/a.py",
+                &format!("{}def call(first: Span, last: Span):\n    pass\n", span)
+            )]),
+            Ok(())
+        );
+
+        let error = check_ident_collisions(&[
+            python_file_with_args("src/a__b.py", "def call(c: Literal['x']):\n    pass\n"),
+            python_file_with_args("src/a.py", "def call(b__c: Literal['y']):\n    pass\n"),
+        ])
+        .unwrap_err();
+
+        assert_eq!(
+            error.message,
+            "`Literal[\"x\"]` in `src/a__b.py` and `Literal[\"y\"]` in `src/a.py` both generate the type `Enum_a__b__c`, please rename a parameter or a class"
+        );
+    }
+
+    #[test]
+    fn test_struct_ident_collisions() {
+        let span = "class Span(TypedDict):\n    label: Literal['PER']\n\n";
+
+        assert_eq!(
+            check_ident_collisions(&[
+                python_file_with_args(
+                    "src/a_b.py",
+                    &format!("{}def call(c: Span):\n    pass\n", span)
+                ),
+                python_file_with_args(
+                    "src/a.py",
+                    &format!("{}def call(b: Span):\n    pass\n", span)
+                ),
+            ]),
+            Ok(())
+        );
+
+        // the enum of `Span.label` and the `Span__label` parameter
+        let error = check_ident_collisions(&[python_file_with_args(
+            "src/a.py",
+            &format!(
+                "{}def call(span: Span, Span__label: Literal['LOC']):\n    pass\n",
+                span
+            ),
+        )])
+        .unwrap_err();
+
+        assert_eq!(
+            error.message,
+            "`Literal[\"PER\"]` in `src/a.py` and `Literal[\"LOC\"]` in `src/a.py` both generate the type `Enum_a__Span__label`, please rename a parameter or a class"
+        );
+
+        let error = check_ident_collisions(&[
+            python_file_with_args(
+                "src/a__b.py",
+                &format!("{}def call(c: Span):\n    pass\n", span),
+            ),
+            python_file_with_args(
+                "src/a.py",
+                "class b__Span(TypedDict):\n    start: int\n\ndef call(c: b__Span):\n    pass\n",
+            ),
+        ])
+        .unwrap_err();
+
+        assert_eq!(
+            error.message,
+            "`Span` in `src/a__b.py` and `b__Span` in `src/a.py` both generate the type `Struct_a__b__Span`, please rename a parameter or a class"
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use pyo3::types::{PyBool, PyDict, PyFloat, PyList, PyLong, PyString, PyTuple};
+use pyo3::PyAny;
+use serde_json::Value;
+
+/// Maximum nesting of the values returned by `call`, deeper values (e.g. a
+/// list containing itself) are rejected.
+const MAX_DEPTH: usize = 128;
+
+/// Converts a value returned by `call` to JSON. `None`, bools, ints, floats,
+/// strings, lists, tuples and dicts with string keys are supported.
+pub fn py_to_json(value: &PyAny, depth: usize) -> Result<Value, String> {
+    if depth > MAX_DEPTH {
+        return Err(format!(
+            "values nested more than {} levels deep are not supported",
+            MAX_DEPTH
+        ));
+    }
+
+    if value.is_none() {
+        Ok(Value::Null)
+    } else if let Ok(value) = value.downcast::<PyBool>() {
+        Ok(Value::Bool(value.is_true()))
+    } else if let Ok(value) = value.downcast::<PyLong>() {
+        value
+            .extract::<i64>()
+            .map(Value::from)
+            .or_else(|_| value.extract::<u64>().map(Value::from))
+            .map_err(|_| format!("the integer {} is too large", value))
+    } else if let Ok(value) = value.downcast::<PyFloat>() {
+        match value.value() {
+            number if number.is_finite() => Ok(Value::from(number)),
+            number => Err(format!("the float {} is not a valid JSON number", number)),
+        }
+    } else if let Ok(value) = value.downcast::<PyString>() {
+        value.to_str().map(Value::from).map_err(|e| e.to_string())
+    } else if let Ok(value) = value.downcast::<PyList>() {
+        value
+            .iter()
+            .map(|item| py_to_json(item, depth + 1))
+            .collect()
+    } else if let Ok(value) = value.downcast::<PyTuple>() {
+        value
+            .iter()
+            .map(|item| py_to_json(item, depth + 1))
+            .collect()
+    } else if let Ok(value) = value.downcast::<PyDict>() {
+        value
+            .iter()
+            .map(|(key, item)| {
+                let key = key
+                    .downcast::<PyString>()
+                    .map_err(|_| format!("the dict key {} is not a string", key))?
+                    .to_str()
+                    .map_err(|e| e.to_string())?;
+
+                Ok((key, py_to_json(item, depth + 1)?))
+            })
+            .collect()
+    } else {
+        Err(format!(
+            "objects of type `{}` can't be converted to JSON",
+            value.get_type().name().unwrap_or("object")
+        ))
+    }
+}
+
+/// Serializes the result of `call`. Strings are expected to be JSON documents
+/// already and are passed through as-is.
+pub fn py_result_to_json(result: &PyAny) -> Result<String, String> {
+    match result.downcast::<PyString>() {
+        Ok(result) => result
+            .to_str()
+            .map(|result| result.to_string())
+            .map_err(|e| e.to_string()),
+        Err(_) => py_to_json(result, 0).map(|result| result.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::Python;
+    use serde_json::json;
+
+    fn eval_to_json(code: &str) -> Result<String, String> {
+        pyo3::prepare_freethreaded_python();
+
+        Python::with_gil(|py| py_result_to_json(py.eval(code, None, None).unwrap()))
+    }
+
+    #[test]
+    fn test_py_result_to_json() {
+        assert_eq!(
+            eval_to_json(
+                r#"{"entities": [("Paris", 0.98)], "count": 1, "next": None, "ok": True}"#
+            )
+            .map(|result| serde_json::from_str::<Value>(&result).unwrap()),
+            Ok(json!({ "entities": [["Paris", 0.98]], "count": 1, "next": null, "ok": true }))
+        );
+
+        assert_eq!(
+            eval_to_json(r#"'{"raw": "json"}'"#).unwrap(),
+            r#"{"raw": "json"}"#
+        );
+        assert_eq!(eval_to_json("-3").unwrap(), "-3");
+    }
+
+    #[test]
+    fn test_py_to_json() {
+        pyo3::prepare_freethreaded_python();
+
+        Python::with_gil(|py| {
+            let eval = |code| py_to_json(py.eval(code, None, None).unwrap(), 0);
+
+            assert_eq!(eval("2 ** 64 - 1"), Ok(json!(u64::MAX)));
+            assert_eq!(
+                eval("2 ** 64"),
+                Err("the integer 18446744073709551616 is too large".to_string())
+            );
+            assert_eq!(eval("'[1]'"), Ok(json!("[1]")));
+            assert_eq!(
+                eval("[(1, 2.5), {'a': [None]}]"),
+                Ok(json!([[1, 2.5], { "a": [null] }]))
+            );
+        });
+    }
+
+    #[test]
+    fn test_unconvertible_result() {
+        assert_eq!(
+            eval_to_json("{1: 'a'}").unwrap_err(),
+            "the dict key 1 is not a string"
+        );
+        assert_eq!(
+            eval_to_json("object()").unwrap_err(),
+            "objects of type `object` can't be converted to JSON"
+        );
+        assert_eq!(
+            eval_to_json("float('nan')").unwrap_err(),
+            "the float NaN is not a valid JSON number"
+        );
+        assert!(eval_to_json("(lambda l: l.append(l) or l)([])").is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use crate::TokenStream2;
+use quote::quote;
+
+pub fn gen_config() -> TokenStream2 {
+    quote! {
+        /// Settings read from the `py_apify` table of the Rocket configuration
+        /// (`Rocket.toml` or `ROCKET_PY_APIFY`).
+        #[derive(Debug, rocket::serde::Deserialize)]
+        #[serde(crate = "rocket::serde", default)]
+        pub struct PyApifyConfig {
+            /// Maximum number of Python hooks running at the same time
+            pub blocking_workers: usize,
+            /// Maximum number of requests waiting for a free worker, extra
+            /// requests are answered with a 503
+            pub queue_depth: usize,
+            /// Number of worker processes per file stem, `"*"` applies to every
+            /// file. Files without worker processes run in this process.
+            pub process_workers: std::collections::HashMap<String, usize>,
+            /// Maximum size of a file uploaded to a `bytes` or
+            /// `py_apify.UploadFile` parameter, e.g. `"10 MiB"`
+            pub max_upload_size: rocket::data::ByteUnit,
+        }
+
+        impl Default for PyApifyConfig {
+            fn default() -> Self {
+                PyApifyConfig {
+                    blocking_workers: 4,
+                    queue_depth: 64,
+                    process_workers: std::collections::HashMap::new(),
+                    max_upload_size: rocket::data::ByteUnit::Mebibyte(10),
+                }
+            }
+        }
+
+        impl PyApifyConfig {
+            pub fn process_workers(&self, file_stem: &str) -> usize {
+                self.process_workers
+                    .get(file_stem)
+                    .or_else(|| self.process_workers.get("*"))
+                    .copied()
+                    .unwrap_or(0)
+            }
+
+            /// Raises the `data-form` limit of Rocket, unless it is configured,
+            /// to accept a file of `max_upload_size` along with the 2 MiB Rocket
+            /// allows for the other fields.
+            pub fn with_upload_limits(&self, figment: rocket::figment::Figment) -> rocket::figment::Figment {
+                if figment.find_value("limits.data-form").is_ok() {
+                    return figment;
+                }
+
+                let data_form_limit = self.max_upload_size + rocket::data::ByteUnit::Mebibyte(2);
+
+                figment.merge(("limits.data-form", data_form_limit.as_u64()))
+            }
+
+            pub fn from_figment(figment: &rocket::figment::Figment) -> Self {
+                match figment.extract_inner::<PyApifyConfig>("py_apify") {
+                    Ok(config) => config,
+                    Err(_) if figment.find_value("py_apify").is_err() => PyApifyConfig::default(),
+                    Err(e) => {
+                        log::error!("Invalid `py_apify` configuration, using defaults: {}", e);
+                        PyApifyConfig::default()
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::TokenStream2;
+use quote::quote;
+
+pub fn gen_constraint() -> TokenStream2 {
+    quote! {
+        /// Values checked by the `Range`, `MinLen` and `MaxLen` constraints
+        /// of `py_apify`. `None` is never checked.
+        trait PyConstrained {
+            fn number(&self) -> Option<f64> {
+                None
+            }
+
+            fn length(&self) -> Option<u64> {
+                None
+            }
+        }
+
+        impl PyConstrained for i64 {
+            fn number(&self) -> Option<f64> {
+                Some(*self as f64)
+            }
+        }
+
+        impl PyConstrained for f64 {
+            fn number(&self) -> Option<f64> {
+                Some(*self)
+            }
+        }
+
+        impl PyConstrained for String {
+            /// Length in characters, like `len` in Python
+            fn length(&self) -> Option<u64> {
+                Some(self.chars().count() as u64)
+            }
+        }
+
+        impl<T> PyConstrained for Vec<T> {
+            fn length(&self) -> Option<u64> {
+                Some(self.len() as u64)
+            }
+        }
+
+        impl<T: PyConstrained> PyConstrained for PyOptional<T> {
+            fn number(&self) -> Option<f64> {
+                self.0.as_ref().and_then(T::number)
+            }
+
+            fn length(&self) -> Option<u64> {
+                self.0.as_ref().and_then(T::length)
+            }
+        }
+
+        /// Form validator of `Range(min, max)`, both bounds included.
+        fn py_range<'v, T: PyConstrained>(value: &T, min: Option<f64>, max: Option<f64>) -> rocket::form::Result<'v, ()> {
+            let number = match value.number() {
+                Some(number) => number,
+                None => return Ok(()),
+            };
+
+            if min.map_or(true, |min| number >= min) && max.map_or(true, |max| number <= max) {
+                return Ok(());
+            }
+
+            let message = match (min, max) {
+                (Some(min), Some(max)) => format!("value must be between {} and {}", min, max),
+                (Some(min), None) => format!("value must be at least {}", min),
+                (None, max) => format!("value must be at most {}", max.unwrap_or_default()),
+            };
+
+            Err(rocket::form::Error::validation(message).into())
+        }
+
+        /// Form validator of `MinLen(length)` and `MaxLen(length)`.
+        fn py_len<'v, T: PyConstrained>(value: &T, min: Option<u64>, max: Option<u64>) -> rocket::form::Result<'v, ()> {
+            match value.length() {
+                Some(length) if min.map_or(false, |min| length < min) || max.map_or(false, |max| length > max) => {
+                    Err(rocket::form::Error::from(rocket::form::error::ErrorKind::InvalidLength { min, max }).into())
+                }
+                _ => Ok(()),
+            }
+        }
+
+        impl FieldError {
+            /// Records the errors of a validator run on a field of a JSON body.
+            fn check(name: &str, expected: &str, result: rocket::form::Result<'_, ()>, errors: &mut Vec<FieldError>) {
+                if let Err(e) = result {
+                    errors.extend(e.iter().map(|error| FieldError {
+                        name: name.to_string(),
+                        expected: Some(expected.to_string()),
+                        reason: error.kind.to_string(),
+                    }));
+                }
+            }
+        }
+    }
+}
@@ -3,9 +3,25 @@ use quote::quote;
 
 pub fn gen_error() -> TokenStream2 {
     quote! {
+        /// A route that matched and forwarded the request rejected its
+        /// arguments, a request matching no route stays a 404.
         #[catch(404)]
         fn invalid_argument(req: &Request) -> Result<(), PyApifyError> {
-            Err(PyApifyError::InvalidArguments)
+            match req.route() {
+                Some(_) => Err(PyApifyError::InvalidArguments),
+                None => Err(PyApifyError::NotFound(format!("{} {}", req.method(), req.uri().path()))),
+            }
+        }
+
+        /// A request rejected with a 422 by a guard, the routes answer the
+        /// fields they reject themselves.
+        #[catch(422)]
+        fn invalid_body(req: &Request) -> Result<(), PyApifyError> {
+            Err(PyApifyError::InvalidFields(vec![FieldError {
+                name: String::new(),
+                expected: None,
+                reason: "the request doesn't match the `call` signature".to_string(),
+            }]))
         }
 
         use std::error;
@@ -13,11 +29,79 @@ pub fn gen_error() -> TokenStream2 {
         use std::fmt;
         use std::path::PathBuf;
 
-        #[derive(Debug)]
+        /// A request field rejected by the validation of a generated form.
+        #[derive(Debug, rocket::serde::Serialize, rocket::serde::Deserialize)]
+        #[serde(crate = "rocket::serde")]
+        pub struct FieldError {
+            /// Name of the parameter, empty for errors about the whole request
+            pub name: String,
+            /// Python type of the parameter, `None` for unknown parameters
+            pub expected: Option<String>,
+            pub reason: String,
+        }
+
+        impl FieldError {
+            fn unknown(name: &str) -> Self {
+                FieldError {
+                    name: name.to_string(),
+                    expected: None,
+                    reason: "unexpected".to_string(),
+                }
+            }
+
+            /// Removes a field from a JSON object and deserializes it. Missing
+            /// fields are deserialized from `null`, so optional ones are `None`.
+            fn json_field<T: rocket::serde::de::DeserializeOwned>(
+                body: &mut rocket::serde::json::Value,
+                name: &str,
+                expected: &str,
+                errors: &mut Vec<FieldError>,
+            ) -> Option<T> {
+                let value = body.as_object_mut().and_then(|object| object.remove(name));
+                let missing = value.is_none();
+
+                T::deserialize(value.unwrap_or(rocket::serde::json::Value::Null))
+                    .map_err(|e| errors.push(FieldError {
+                        name: name.to_string(),
+                        expected: Some(expected.to_string()),
+                        reason: if missing { "missing".to_string() } else { e.to_string() },
+                    }))
+                    .ok()
+            }
+        }
+
+        impl fmt::Display for FieldError {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                match (self.name.as_str(), &self.expected) {
+                    ("", _) => write!(f, "{}", self.reason),
+                    (name, Some(expected)) => write!(f, "`{}` ({}): {}", name, expected, self.reason),
+                    (name, None) => write!(f, "`{}`: {}", name, self.reason),
+                }
+            }
+        }
+
+        #[derive(Debug, rocket::serde::Serialize, rocket::serde::Deserialize)]
+        #[serde(crate = "rocket::serde")]
         pub enum PyApifyError {
             HookFunctionNotFound(String),
             HookFunctionFailure(String),
+            /// No route matches the method and path of the request
+            NotFound(String),
             InvalidArguments,
+            /// The request fields don't match the parameters of `call`
+            InvalidFields(Vec<FieldError>),
+            Overloaded,
+            /// The Python file failed to import at startup, see `/readyz`
+            ModuleNotLoaded(String),
+            WorkerFailure(String),
+            InvalidReturnValue(String),
+            /// Exception raised by a hook and answered with a client or
+            /// server error status, see `PyApifyError::from_py_err`
+            PythonException {
+                status: u16,
+                exception: String,
+                detail: String,
+            },
         }
 
         impl fmt::Display for PyApifyError {
@@ -27,8 +111,22 @@ pub fn gen_error() -> TokenStream2 {
                         write!(f, "Failed to call the `call` function inside your Python file {}. Your python file must contain a `call` function", file_name),
                     PyApifyError::HookFunctionFailure(error_message) =>
                         write!(f, "The hook function returned an error : {}", error_message),
+                    PyApifyError::NotFound(route) =>
+                        write!(f, "No route matches {}", route),
                     PyApifyError::InvalidArguments =>
-                        write!(f, "Invalid arguments")
+                        write!(f, "Invalid arguments"),
+                    PyApifyError::InvalidFields(errors) =>
+                        write!(f, "Invalid arguments: {}", errors.iter().map(|e| e.to_string()).collect::<Vec<String>>().join(", ")),
+                    PyApifyError::Overloaded =>
+                        write!(f, "Too many requests are waiting for a Python worker, retry later"),
+                    PyApifyError::ModuleNotLoaded(error_message) =>
+                        write!(f, "The Python file failed to import at startup : {}", error_message),
+                    PyApifyError::WorkerFailure(error_message) =>
+                        write!(f, "The Python worker failed : {}", error_message),
+                    PyApifyError::InvalidReturnValue(error_message) =>
+                        write!(f, "The hook function returned a value that can't be converted to JSON : {}", error_message),
+                    PyApifyError::PythonException { exception, detail, .. } =>
+                        write!(f, "{}: {}", exception, detail)
                 }
             }
         }
@@ -42,20 +140,87 @@ pub fn gen_error() -> TokenStream2 {
         use rocket::response::{self, Response, Responder};
         use rocket::http::ContentType;
 
-        impl<'a> Responder<'a, 'a> for PyApifyError {
-            fn respond_to(self, _: &Request) -> response::Result<'a> {
-                let error_message = format!("{{\"error\": \"{}\"}}", self);
-                let error_messag_len = error_message.len();
+        impl PyApifyError {
+            fn kind(&self) -> &'static str {
+                match self {
+                    PyApifyError::HookFunctionNotFound(_) => "hook-function-not-found",
+                    PyApifyError::HookFunctionFailure(_) => "hook-function-failure",
+                    PyApifyError::NotFound(_) => "not-found",
+                    PyApifyError::InvalidArguments => "invalid-arguments",
+                    PyApifyError::InvalidFields(_) => "invalid-fields",
+                    PyApifyError::Overloaded => "overloaded",
+                    PyApifyError::ModuleNotLoaded(_) => "module-not-loaded",
+                    PyApifyError::WorkerFailure(_) => "worker-failure",
+                    PyApifyError::InvalidReturnValue(_) => "invalid-return-value",
+                    PyApifyError::PythonException { .. } => "python-exception",
+                }
+            }
 
-                let status = match self {
+            fn title(&self) -> &'static str {
+                match self {
+                    PyApifyError::HookFunctionNotFound(_) => "The Python file has no `call` function",
+                    PyApifyError::HookFunctionFailure(_) => "The `call` function raised an exception",
+                    PyApifyError::NotFound(_) => "Not found",
+                    PyApifyError::InvalidArguments => "Invalid arguments",
+                    PyApifyError::InvalidFields(_) => "Invalid arguments",
+                    PyApifyError::Overloaded => "Too many requests are waiting for a Python worker",
+                    PyApifyError::ModuleNotLoaded(_) => "The Python file is not loaded",
+                    PyApifyError::WorkerFailure(_) => "The Python worker failed",
+                    PyApifyError::InvalidReturnValue(_) => "The `call` function returned a value that can't be converted to JSON",
+                    PyApifyError::PythonException { .. } => "The `call` function rejected the request",
+                }
+            }
+
+            fn status(&self) -> Status {
+                match self {
+                    Self::NotFound(_) => Status::NotFound,
                     Self::InvalidArguments => Status::BadRequest,
+                    Self::InvalidFields(_) => Status::UnprocessableEntity,
+                    Self::Overloaded | Self::ModuleNotLoaded(_) => Status::ServiceUnavailable,
+                    Self::PythonException { status, .. } => Status::from_code(*status)
+                        .filter(|status| status.code >= 400)
+                        .unwrap_or(Status::InternalServerError),
                     _ => Status::InternalServerError,
+                }
+            }
+        }
+
+        /// Errors are answered with an RFC 7807 `application/problem+json`
+        /// document, whose `instance` is the id of the request.
+        impl<'r> Responder<'r, 'static> for PyApifyError {
+            fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+                let status = self.status();
+
+                let detail = match &self {
+                    Self::PythonException { detail, .. } => detail.clone(),
+                    _ => self.to_string(),
                 };
 
+                let mut problem = py_apify_core::problem::problem(
+                    self.kind(),
+                    self.title(),
+                    status.code,
+                    &detail,
+                    &RequestId::of(req).0,
+                );
+
+                match &self {
+                    Self::PythonException { exception, .. } => {
+                        problem["exception"] = rocket::serde::json::Value::from(exception.as_str());
+                    }
+                    Self::InvalidFields(errors) => {
+                        problem["fields"] = rocket::serde::json::json!(errors);
+                    }
+                    _ => {}
+                }
+
+                let problem = problem.to_string();
+                let (top, sub) = py_apify_core::problem::PROBLEM_MEDIA_TYPE;
+
                 Response::build()
-                    .header(ContentType::JSON)
+                    .header(ContentType::new(top, sub))
                     .status(status)
-                    .sized_body(error_messag_len, Cursor::new(error_message))
+                    .sized_body(problem.len(), Cursor::new(problem))
                     .ok()
             }
         }
@@ -3,6 +3,7 @@ use proc_macro2::{Literal, TokenStream as TokenStream2};
 use quote::quote;
 
 pub struct PythonFileLoader {
+    file_stem: Literal,
     module_path: Literal,
     module_name: Literal,
     file_name: Literal,
@@ -11,13 +12,9 @@ pub struct PythonFileLoader {
 impl<'a> From<&'a PythonFile> for PythonFileLoader {
     fn from(python_file: &'a PythonFile) -> PythonFileLoader {
         PythonFileLoader {
-            module_path: Literal::string(
-                std::fs::canonicalize(&python_file.path)
-                    .unwrap()
-                    .to_str()
-                    .unwrap(),
-            ),
-            module_name: Literal::string(&python_file.uuid),
+            file_stem: Literal::string(&python_file.file_stem),
+            module_path: Literal::string(&python_file.absolute_path()),
+            module_name: Literal::string(&python_file.module_name()),
             file_name: Literal::string(&python_file.file_name),
         }
     }
@@ -25,17 +22,70 @@ impl<'a> From<&'a PythonFile> for PythonFileLoader {
 
 impl From<PythonFileLoader> for TokenStream2 {
     fn from(python_file_loader: PythonFileLoader) -> Self {
+        let file_stem: Literal = python_file_loader.file_stem;
         let file_name: Literal = python_file_loader.file_name;
         let module_name: Literal = python_file_loader.module_name;
         let module_path: Literal = python_file_loader.module_path;
 
+        // in development mode the files are read from disk as long as the
+        // signature of `call` is unchanged, see `hot_reload.rs`
+        let code = if cfg!(feature = "hot-reload") {
+            quote! {
+                &py_apify_core::hot_reload::startup_code(
+                    include_str!(#module_path),
+                    std::fs::read_to_string(#module_path),
+                    #file_name,
+                )
+            }
+        } else {
+            quote! { include_str!(#module_path) }
+        };
+
         quote! {
-            pyo3::types::PyModule::from_code(
-                py,
-                include_str!(#module_path),
-                #file_name,
-                #module_name,
-            );
+            #file_stem => {
+                import_py_apify_module(py)?.call_method1("_register_file", (#file_name, #file_stem))?;
+
+                pyo3::types::PyModule::from_code(
+                    py,
+                    #code,
+                    #file_name,
+                    #module_name,
+                ).map(|_| ())
+            }
+        }
+    }
+}
+
+/// Generates `load_py_module`, which imports the Python file matching a file
+/// stem into the interpreter, after the `py_apify` helpers module. The file is
+/// registered in `py_apify` so that its logs use the file stem as target.
+pub struct PythonFileLoaders {
+    loaders: Vec<PythonFileLoader>,
+}
+
+impl From<&Vec<PythonFile>> for PythonFileLoaders {
+    fn from(python_files: &Vec<PythonFile>) -> PythonFileLoaders {
+        PythonFileLoaders {
+            loaders: python_files.iter().map(PythonFileLoader::from).collect(),
+        }
+    }
+}
+
+impl From<PythonFileLoaders> for TokenStream2 {
+    fn from(python_file_loaders: PythonFileLoaders) -> Self {
+        let loaders: Vec<TokenStream2> = python_file_loaders
+            .loaders
+            .into_iter()
+            .map(|loader| loader.into())
+            .collect();
+
+        quote! {
+            fn load_py_module(py: pyo3::Python, file_stem: &str) -> pyo3::PyResult<()> {
+                match file_stem {
+                    #(#loaders)*
+                    _ => Err(pyo3::exceptions::PyImportError::new_err(format!("unknown Python file `{}`", file_stem))),
+                }
+            }
         }
     }
 }
@@ -50,28 +100,32 @@ mod tests {
         let py_file = PythonFile {
             file_name: "test.py".into(),
             file_stem: "test".into(),
-            uuid: "27597466".into(),
+            ident: "test".into(),
             main_func_args: vec![],
+            doc: None,
             path: PathBuf::from("test_py/test.py"),
         };
 
         let token_stream: TokenStream2 = PythonFileLoader::from(&py_file).into();
 
-        let full_file_path = Literal::string(&format!(
-            "{}",
+        let full_file_path = Literal::string(
             std::fs::canonicalize(&py_file.path)
                 .unwrap()
                 .to_str()
-                .unwrap()
-        ));
+                .unwrap(),
+        );
 
         let target_ts = quote! {
-            pyo3::types::PyModule::from_code(
-                py,
-                include_str!(#full_file_path),
-                "test.py",
-                "27597466",
-            );
+            "test" => {
+                import_py_apify_module(py)?.call_method1("_register_file", ("test.py", "test"))?;
+
+                pyo3::types::PyModule::from_code(
+                    py,
+                    include_str!(#full_file_path),
+                    "test.py",
+                    "py_apify_test",
+                ).map(|_| ())
+            }
         };
 
         assert_eq!(token_stream.to_string(), target_ts.to_string());
@@ -1,8 +1,235 @@
 use crate::python_file::PythonFile;
 use proc_macro2::{Ident, Literal, Span, TokenStream as TokenStream2};
 use quote::quote;
+use std::collections::HashSet;
 
-use crate::py_arg::PyArg;
+use crate::py_arg::{
+    PyArg, PyConstraint, PyDefault, PyEnum, PyPrimitiveDataType, PyStruct, PyStructKind,
+};
+
+/// Builds the statements pushing a struct field into the `call` kwargs.
+///
+/// Defaults known at compile time are applied on the Rust side. Parameters whose
+/// default is a Python expression are left out when missing, so Python can
+/// evaluate it.
+fn kwarg(py_arg: &PyArg) -> TokenStream2 {
+    let field_ident: Ident = py_arg.clone().into();
+    let field_name = Literal::string(&py_arg.name);
+
+    // optional parameters are wrapped in a `PyOptional`
+    let value = match py_arg.optional {
+        true => quote! { self.#field_ident.0 },
+        false => quote! { self.#field_ident },
+    };
+
+    match (py_arg.default_tokens(), &py_arg.default) {
+        (Some(default), _) => quote! {
+            let py_any : pyo3::Py<pyo3::PyAny> = #value.unwrap_or_else(|| #default).into_py(py);
+            args.push((#field_name, py_any));
+        },
+        (None, Some(PyDefault::None)) | (None, None) => quote! {
+            let py_any : pyo3::Py<pyo3::PyAny> = #value.into_py(py);
+            args.push((#field_name, py_any));
+        },
+        (None, Some(_)) => quote! {
+            let py_any : pyo3::Py<pyo3::PyAny> = #value.into_py(py);
+
+            if !py_any.is_none(py) {
+                args.push((#field_name, py_any));
+            }
+        },
+    }
+}
+
+fn optional_f64(value: Option<f64>) -> TokenStream2 {
+    match value {
+        Some(value) if value < 0.0 => {
+            let literal = Literal::f64_suffixed(-value);
+            quote! { Some(-#literal) }
+        }
+        Some(value) => {
+            let literal = Literal::f64_suffixed(value);
+            quote! { Some(#literal) }
+        }
+        None => quote! { None },
+    }
+}
+
+/// Validator function of a constraint and its arguments, the value being
+/// validated excluded.
+fn validator(constraint: &PyConstraint) -> (Ident, TokenStream2) {
+    match constraint {
+        PyConstraint::Range { min, max } => {
+            let min = optional_f64(*min);
+            let max = optional_f64(*max);
+            (
+                Ident::new("py_range", Span::call_site()),
+                quote! { #min, #max },
+            )
+        }
+        PyConstraint::MinLen(length) => {
+            let length = Literal::u64_suffixed(*length);
+            (
+                Ident::new("py_len", Span::call_site()),
+                quote! { Some(#length), None },
+            )
+        }
+        PyConstraint::MaxLen(length) => {
+            let length = Literal::u64_suffixed(*length);
+            (
+                Ident::new("py_len", Span::call_site()),
+                quote! { None, Some(#length) },
+            )
+        }
+    }
+}
+
+/// Struct field of a parameter, validated by Rocket for query strings and forms.
+fn struct_field(py_arg: &PyArg) -> TokenStream2 {
+    let field: TokenStream2 = py_arg.clone().into();
+    let attributes = py_arg.constraints.iter().map(|constraint| {
+        let (validator, args) = validator(constraint);
+        quote! { #[field(validate = #validator(#args))] }
+    });
+
+    quote! {
+        #(#attributes)*
+        #field
+    }
+}
+
+/// Runs the validators of a parameter on a field of a JSON body.
+fn json_checks(py_arg: &PyArg) -> TokenStream2 {
+    let field_ident: Ident = py_arg.clone().into();
+    let field_name = Literal::string(&py_arg.name);
+    let python_type = Literal::string(&python_type(py_arg));
+
+    let checks = py_arg.constraints.iter().map(|constraint| {
+        let (validator, args) = validator(constraint);
+        quote! {
+            FieldError::check(#field_name, #python_type, #validator(value, #args), &mut errors);
+        }
+    });
+
+    match py_arg.constraints.is_empty() {
+        true => quote! {},
+        false => quote! {
+            if let Some(value) = &#field_ident {
+                #(#checks)*
+            }
+        },
+    }
+}
+
+/// Python type of a parameter, as shown in validation errors.
+fn python_type(py_arg: &PyArg) -> String {
+    match py_arg.optional {
+        true => format!("{} | None", py_arg.data_type.python_name()),
+        false => py_arg.data_type.python_name(),
+    }
+}
+
+/// Builds the Rust enum of a `Literal` or `Enum` parameter. Python receives
+/// the value itself for a `Literal`, the member of the class for an `Enum`.
+fn enum_type(py_enum: &PyEnum, module_name: &str) -> TokenStream2 {
+    let ident = Ident::new(&py_enum.ident, Span::call_site());
+    let variants = py_enum.variant_idents();
+    let values: Vec<Literal> = py_enum
+        .values
+        .iter()
+        .map(|value| Literal::string(value))
+        .collect();
+
+    let into_py = match &py_enum.class_name {
+        Some(class_name) => {
+            let module_name = Literal::string(module_name);
+            let class_name = Literal::string(class_name);
+
+            quote! {
+                py.import(#module_name)
+                    .and_then(|module| module.getattr(#class_name))
+                    .and_then(|class| class.call1((value,)))
+                    .map(|member| member.into())
+                    .unwrap_or_else(|e| {
+                        log::error!("Failed to get the `{}` member of `{}`: {}", value, #class_name, e);
+                        value.into_py(py)
+                    })
+            }
+        }
+        None => quote! { value.into_py(py) },
+    };
+
+    quote! {
+        #[derive(Clone, Copy, rocket::form::FromFormField, rocket::serde::Serialize, rocket::serde::Deserialize)]
+        #[serde(crate = "rocket::serde")]
+        enum #ident {
+            #(
+                #[field(value = #values)]
+                #[serde(rename = #values)]
+                #variants,
+            )*
+        }
+
+        impl pyo3::IntoPy<pyo3::PyObject> for #ident {
+            fn into_py(self, py: pyo3::Python) -> pyo3::PyObject {
+                let value = match self {
+                    #(#ident::#variants => #values,)*
+                };
+
+                #into_py
+            }
+        }
+    }
+}
+
+/// Builds the Rust struct of a `TypedDict` or dataclass parameter. Python
+/// receives a dict for a `TypedDict`, an instance of the class for a dataclass.
+fn struct_type(py_struct: &PyStruct, module_name: &str) -> TokenStream2 {
+    let ident = Ident::new(&py_struct.ident, Span::call_site());
+    let fields: Vec<TokenStream2> = py_struct.fields.iter().map(struct_field).collect();
+    let kwargs: Vec<TokenStream2> = py_struct.fields.iter().map(kwarg).collect();
+
+    let into_py = match py_struct.kind {
+        PyStructKind::TypedDict => quote! { fields.into() },
+        PyStructKind::Dataclass => {
+            let module_name = Literal::string(module_name);
+            let class_name = Literal::string(&py_struct.class_name);
+
+            quote! {
+                py.import(#module_name)
+                    .and_then(|module| module.getattr(#class_name))
+                    .and_then(|class| class.call((), Some(fields)))
+                    .map(|instance| instance.into())
+                    .unwrap_or_else(|e| {
+                        log::error!("Failed to build a `{}`: {}", #class_name, e);
+                        fields.into()
+                    })
+            }
+        }
+    };
+
+    quote! {
+        #[derive(rocket::form::FromForm, rocket::serde::Serialize, rocket::serde::Deserialize)]
+        #[serde(crate = "rocket::serde", deny_unknown_fields)]
+        struct #ident {
+            #(#fields),*
+        }
+
+        impl pyo3::IntoPy<pyo3::PyObject> for #ident {
+            fn into_py(self, py: pyo3::Python) -> pyo3::PyObject {
+                use pyo3::types::IntoPyDict;
+
+                let mut args : Vec<(&str, pyo3::Py<pyo3::PyAny>)> = vec!();
+
+                #(#kwargs)*
+
+                let fields = args.into_py_dict(py);
+
+                #into_py
+            }
+        }
+    }
+}
 
 pub struct FormIdent {
     ident: Ident,
@@ -11,7 +238,7 @@ pub struct FormIdent {
 impl From<&PythonFile> for FormIdent {
     fn from(python_file: &PythonFile) -> FormIdent {
         FormIdent {
-            ident: Ident::new(&format!("Form_{}", python_file.uuid), Span::call_site()),
+            ident: Ident::new(&format!("Form_{}", python_file.ident), Span::call_site()),
         }
     }
 }
@@ -25,6 +252,9 @@ impl From<FormIdent> for Ident {
 pub struct Form {
     ident: FormIdent,
     variants: Vec<PyArg>,
+    /// Files can't be sent in a JSON body, `from_json` is left out
+    multipart: bool,
+    module_name: String,
 }
 
 impl From<&PythonFile> for Form {
@@ -32,6 +262,8 @@ impl From<&PythonFile> for Form {
         Form {
             ident: FormIdent::from(python_file),
             variants: python_file.main_func_args.clone(),
+            multipart: python_file.has_uploads(),
+            module_name: python_file.module_name(),
         }
     }
 }
@@ -39,37 +271,88 @@ impl From<&PythonFile> for Form {
 impl From<Form> for TokenStream2 {
     fn from(form: Form) -> Self {
         let form_ident: Ident = form.ident.into();
-        let struct_fields: Vec<TokenStream2> = form
-            .variants
-            .clone()
+        let struct_fields: Vec<TokenStream2> = form.variants.iter().map(struct_field).collect();
+        let json_checks: Vec<TokenStream2> = form.variants.iter().map(json_checks).collect();
+
+        let kwargs: Vec<TokenStream2> = form.variants.iter().map(kwarg).collect();
+
+        let mut types = vec![];
+        for variant in &form.variants {
+            variant.data_type.named_types(&mut types);
+        }
+
+        // a class used by several parameters is generated once
+        let module_name = &form.module_name;
+        let mut type_idents = HashSet::new();
+        let named_types: Vec<TokenStream2> = types
             .into_iter()
-            .map(|variant| variant.into())
+            .filter_map(|data_type| match data_type {
+                PyPrimitiveDataType::Enum(py_enum) if type_idents.insert(&py_enum.ident) => {
+                    Some(enum_type(py_enum, module_name))
+                }
+                PyPrimitiveDataType::Struct(py_struct) if type_idents.insert(&py_struct.ident) => {
+                    Some(struct_type(py_struct, module_name))
+                }
+                _ => None,
+            })
             .collect();
 
-        let struct_fields_names: Vec<TokenStream2> = form
+        let field_idents: Vec<Ident> = form
             .variants
             .iter()
-            .map(|variant| {
-                let literal_name = Literal::string(&variant.name);
-                quote! {
-                    #literal_name
-                }
-            })
+            .map(|variant| variant.clone().into())
             .collect();
-
-        let struct_fields_values: Vec<TokenStream2> = form
+        let field_names: Vec<Literal> = form
             .variants
-            .into_iter()
-            .map(|field| {
-                let variant_ident: Ident = field.into();
-                quote! {
-                    self.#variant_ident
-                }
-            })
+            .iter()
+            .map(|variant| Literal::string(&variant.name))
+            .collect();
+        let python_types: Vec<Literal> = form
+            .variants
+            .iter()
+            .map(|variant| Literal::string(&python_type(variant)))
             .collect();
 
+        let from_json = if form.multipart {
+            quote! {}
+        } else {
+            quote! {
+                /// Deserializes a JSON body field by field, so that every
+                /// invalid field is reported.
+                pub fn from_json(mut body: rocket::serde::json::Value) -> Result<Self, Vec<FieldError>> {
+                    if !body.is_object() {
+                        return Err(vec![FieldError {
+                            name: String::new(),
+                            expected: Some("object".to_string()),
+                            reason: "the body must be a JSON object".to_string(),
+                        }]);
+                    }
+
+                    let mut errors = vec![];
+
+                    #(
+                        let #field_idents = FieldError::json_field(&mut body, #field_names, #python_types, &mut errors);
+                    )*
+
+                    #(#json_checks)*
+
+                    if let Some(object) = body.as_object() {
+                        errors.extend(object.keys().map(|name| FieldError::unknown(name)));
+                    }
+
+                    match (#(#field_idents,)*) {
+                        (#(Some(#field_idents),)*) if errors.is_empty() => Ok(#form_ident { #(#field_idents),* }),
+                        _ => Err(errors),
+                    }
+                }
+            }
+        };
+
         quote! {
-            #[derive(rocket::form::FromForm)]
+            #(#named_types)*
+
+            #[derive(rocket::form::FromForm, rocket::serde::Serialize, rocket::serde::Deserialize)]
+            #[serde(crate = "rocket::serde", deny_unknown_fields)]
             struct #form_ident {
                 #(#struct_fields),*
             }
@@ -81,17 +364,36 @@ impl From<Form> for TokenStream2 {
 
                     let mut args : Vec<(&str, pyo3::Py<pyo3::PyAny>)> = vec!();
 
-                    #(
-                        let struct_field_value = #struct_fields_values;
-                        let py_any : pyo3::Py<pyo3::PyAny> = struct_field_value.into_py(py);
-
-                        if !py_any.is_none(py) {
-                            args.push((#struct_fields_names, py_any));
-                        }
-                    )*
+                    #(#kwargs)*
 
                     args.into_py_dict(py)
                 }
+
+                fn python_type(name: &str) -> Option<&'static str> {
+                    match name {
+                        #(#field_names => Some(#python_types),)*
+                        _ => None,
+                    }
+                }
+
+                /// Lists the fields of a rejected query string or multipart form.
+                pub fn field_errors(errors: rocket::form::Errors<'_>) -> Vec<FieldError> {
+                    errors
+                        .iter()
+                        .map(|error| {
+                            let name = error.name.as_ref().map(|name| name.to_string()).unwrap_or_default();
+                            let name = name.split(|c| c == '.' || c == '[').next().unwrap_or_default();
+
+                            FieldError {
+                                name: name.to_string(),
+                                expected: Self::python_type(name).map(String::from),
+                                reason: error.kind.to_string(),
+                            }
+                        })
+                        .collect()
+                }
+
+                #from_json
             }
         }
     }
@@ -100,7 +402,6 @@ impl From<Form> for TokenStream2 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::py_arg::{PyArg, PyPrimitiveDataType};
     use std::path::PathBuf;
 
     #[test]
@@ -108,57 +409,384 @@ mod tests {
         let py_file = PythonFile {
             file_name: "test.py".into(),
             file_stem: "test".into(),
-            uuid: "27597466".into(),
+            ident: "test".into(),
             main_func_args: vec![
                 PyArg {
                     name: "input".into(),
                     data_type: PyPrimitiveDataType::Str,
                     optional: false,
+                    default: None,
+                    constraints: vec![],
+                    doc: None,
                 },
                 PyArg {
                     name: "score".into(),
                     data_type: PyPrimitiveDataType::Int,
                     optional: true,
+                    default: Some(PyDefault::Int(5)),
+                    constraints: vec![],
+                    doc: None,
+                },
+                PyArg {
+                    name: "threshold".into(),
+                    data_type: PyPrimitiveDataType::Float,
+                    optional: true,
+                    default: Some(PyDefault::Expression),
+                    constraints: vec![],
+                    doc: None,
                 },
             ],
+            doc: None,
             path: PathBuf::from("test_py/test.py"),
         };
 
         let token_stream: TokenStream2 = Form::from(&py_file).into();
 
         let target_ts = quote! {
-            #[derive(rocket::form::FromForm)]
-            struct Form_27597466 {
+            #[derive(rocket::form::FromForm, rocket::serde::Serialize, rocket::serde::Deserialize)]
+            #[serde(crate = "rocket::serde", deny_unknown_fields)]
+            struct Form_test {
                 input: String,
-                score: Option<usize>
+                score: PyOptional<i64>,
+                threshold: PyOptional<f64>
             }
 
-            impl Form_27597466 {
+            impl Form_test {
                 pub fn kwargs(self, py: pyo3::prelude::Python) -> &pyo3::types::PyDict {
                     use pyo3::types::IntoPyDict;
                     use pyo3::conversion::IntoPy;
 
                     let mut args : Vec<(&str, pyo3::Py<pyo3::PyAny>)> = vec!();
 
-                    let struct_field_value = self.input;
-                    let py_any : pyo3::Py<pyo3::PyAny> = struct_field_value.into_py(py);
+                    let py_any : pyo3::Py<pyo3::PyAny> = self.input.into_py(py);
+                    args.push(("input", py_any));
 
-                    if !py_any.is_none(py) {
-                        args.push(("input", py_any));
-                    }
+                    let py_any : pyo3::Py<pyo3::PyAny> = self.score.0.unwrap_or_else(|| 5).into_py(py);
+                    args.push(("score", py_any));
 
-                    let struct_field_value = self.score;
-                    let py_any : pyo3::Py<pyo3::PyAny> = struct_field_value.into_py(py);
+                    let py_any : pyo3::Py<pyo3::PyAny> = self.threshold.0.into_py(py);
 
                     if !py_any.is_none(py) {
-                        args.push(("score", py_any));
+                        args.push(("threshold", py_any));
                     }
 
                     args.into_py_dict(py)
                 }
+
+                fn python_type(name: &str) -> Option<&'static str> {
+                    match name {
+                        "input" => Some("str"),
+                        "score" => Some("int | None"),
+                        "threshold" => Some("float | None"),
+                        _ => None,
+                    }
+                }
+
+                /// Lists the fields of a rejected query string or multipart form.
+                pub fn field_errors(errors: rocket::form::Errors<'_>) -> Vec<FieldError> {
+                    errors
+                        .iter()
+                        .map(|error| {
+                            let name = error.name.as_ref().map(|name| name.to_string()).unwrap_or_default();
+                            let name = name.split(|c| c == '.' || c == '[').next().unwrap_or_default();
+
+                            FieldError {
+                                name: name.to_string(),
+                                expected: Self::python_type(name).map(String::from),
+                                reason: error.kind.to_string(),
+                            }
+                        })
+                        .collect()
+                }
+
+                /// Deserializes a JSON body field by field, so that every
+                /// invalid field is reported.
+                pub fn from_json(mut body: rocket::serde::json::Value) -> Result<Self, Vec<FieldError>> {
+                    if !body.is_object() {
+                        return Err(vec![FieldError {
+                            name: String::new(),
+                            expected: Some("object".to_string()),
+                            reason: "the body must be a JSON object".to_string(),
+                        }]);
+                    }
+
+                    let mut errors = vec![];
+
+                    let input = FieldError::json_field(&mut body, "input", "str", &mut errors);
+                    let score = FieldError::json_field(&mut body, "score", "int | None", &mut errors);
+                    let threshold = FieldError::json_field(&mut body, "threshold", "float | None", &mut errors);
+
+                    if let Some(object) = body.as_object() {
+                        errors.extend(object.keys().map(|name| FieldError::unknown(name)));
+                    }
+
+                    match (input, score, threshold,) {
+                        (Some(input), Some(score), Some(threshold),) if errors.is_empty() => Ok(Form_test { input, score, threshold }),
+                        _ => Err(errors),
+                    }
+                }
             }
         };
 
         assert_eq!(token_stream.to_string(), target_ts.to_string());
     }
+
+    #[test]
+    fn test_enum_form() {
+        let py_file = PythonFile {
+            file_name: "test.py".into(),
+            file_stem: "test".into(),
+            ident: "test".into(),
+            main_func_args: vec![PyArg {
+                name: "mode".into(),
+                data_type: PyPrimitiveDataType::Enum(PyEnum {
+                    ident: "Enum_test__mode".into(),
+                    class_name: Some("Mode".into()),
+                    values: vec!["fast".into(), "accurate".into()],
+                }),
+                optional: true,
+                default: Some(PyDefault::Str("fast".into())),
+                constraints: vec![],
+                doc: None,
+            }],
+            doc: None,
+            path: PathBuf::from("test_py/test.py"),
+        };
+
+        let token_stream = TokenStream2::from(Form::from(&py_file)).to_string();
+
+        let enum_ts = quote! {
+            #[derive(Clone, Copy, rocket::form::FromFormField, rocket::serde::Serialize, rocket::serde::Deserialize)]
+            #[serde(crate = "rocket::serde")]
+            enum Enum_test__mode {
+                #[field(value = "fast")]
+                #[serde(rename = "fast")]
+                V0,
+                #[field(value = "accurate")]
+                #[serde(rename = "accurate")]
+                V1,
+            }
+
+            impl pyo3::IntoPy<pyo3::PyObject> for Enum_test__mode {
+                fn into_py(self, py: pyo3::Python) -> pyo3::PyObject {
+                    let value = match self {
+                        Enum_test__mode::V0 => "fast",
+                        Enum_test__mode::V1 => "accurate",
+                    };
+
+                    py.import("py_apify_test")
+                        .and_then(|module| module.getattr("Mode"))
+                        .and_then(|class| class.call1((value,)))
+                        .map(|member| member.into())
+                        .unwrap_or_else(|e| {
+                            log::error!("Failed to get the `{}` member of `{}`: {}", value, "Mode", e);
+                            value.into_py(py)
+                        })
+                }
+            }
+        };
+
+        assert!(token_stream.starts_with(&enum_ts.to_string()));
+        assert!(token_stream.contains(&quote! { mode: PyOptional<Enum_test__mode> }.to_string()));
+        assert!(token_stream.contains(
+            &quote! { self.mode.0.unwrap_or_else(|| Enum_test__mode::V0).into_py(py) }.to_string()
+        ));
+    }
+
+    #[test]
+    fn test_struct_form() {
+        let span = PyStruct {
+            ident: "Struct_test__Document__Span".into(),
+            class_name: "Span".into(),
+            kind: PyStructKind::TypedDict,
+            fields: vec![PyArg {
+                name: "start".into(),
+                data_type: PyPrimitiveDataType::Int,
+                optional: false,
+                default: None,
+                constraints: vec![],
+                doc: None,
+            }],
+        };
+        let document = PyStruct {
+            ident: "Struct_test__Document".into(),
+            class_name: "Document".into(),
+            kind: PyStructKind::Dataclass,
+            fields: vec![
+                PyArg {
+                    name: "spans".into(),
+                    data_type: PyPrimitiveDataType::List(Box::new(PyPrimitiveDataType::Struct(
+                        span,
+                    ))),
+                    optional: false,
+                    default: None,
+                    constraints: vec![],
+                    doc: None,
+                },
+                PyArg {
+                    name: "lang".into(),
+                    data_type: PyPrimitiveDataType::Str,
+                    optional: true,
+                    default: Some(PyDefault::Str("fr".into())),
+                    constraints: vec![],
+                    doc: None,
+                },
+            ],
+        };
+        let py_file = PythonFile {
+            file_name: "test.py".into(),
+            file_stem: "test".into(),
+            ident: "test".into(),
+            main_func_args: vec![PyArg {
+                name: "doc".into(),
+                data_type: PyPrimitiveDataType::Struct(document),
+                optional: false,
+                default: None,
+                constraints: vec![],
+                doc: None,
+            }],
+            doc: None,
+            path: PathBuf::from("test_py/test.py"),
+        };
+
+        let token_stream = TokenStream2::from(Form::from(&py_file)).to_string();
+
+        let struct_ts = quote! {
+            #[derive(rocket::form::FromForm, rocket::serde::Serialize, rocket::serde::Deserialize)]
+            #[serde(crate = "rocket::serde", deny_unknown_fields)]
+            struct Struct_test__Document__Span {
+                start: i64
+            }
+
+            impl pyo3::IntoPy<pyo3::PyObject> for Struct_test__Document__Span {
+                fn into_py(self, py: pyo3::Python) -> pyo3::PyObject {
+                    use pyo3::types::IntoPyDict;
+
+                    let mut args : Vec<(&str, pyo3::Py<pyo3::PyAny>)> = vec!();
+
+                    let py_any : pyo3::Py<pyo3::PyAny> = self.start.into_py(py);
+                    args.push(("start", py_any));
+
+                    let fields = args.into_py_dict(py);
+
+                    fields.into()
+                }
+            }
+
+            #[derive(rocket::form::FromForm, rocket::serde::Serialize, rocket::serde::Deserialize)]
+            #[serde(crate = "rocket::serde", deny_unknown_fields)]
+            struct Struct_test__Document {
+                spans: Vec<Struct_test__Document__Span>,
+                lang: PyOptional<String>
+            }
+        };
+
+        assert!(token_stream.starts_with(&struct_ts.to_string()));
+        assert!(token_stream.contains(
+            &quote! {
+                py.import("py_apify_test")
+                    .and_then(|module| module.getattr("Document"))
+                    .and_then(|class| class.call((), Some(fields)))
+            }
+            .to_string()
+        ));
+        assert!(token_stream.contains(&quote! { doc: Struct_test__Document }.to_string()));
+    }
+
+    #[test]
+    fn test_constrained_form() {
+        let py_file = PythonFile {
+            file_name: "test.py".into(),
+            file_stem: "test".into(),
+            ident: "test".into(),
+            main_func_args: vec![
+                PyArg {
+                    name: "text".into(),
+                    data_type: PyPrimitiveDataType::Str,
+                    optional: false,
+                    default: None,
+                    constraints: vec![PyConstraint::MinLen(1), PyConstraint::MaxLen(100)],
+                    doc: None,
+                },
+                PyArg {
+                    name: "top_k".into(),
+                    data_type: PyPrimitiveDataType::Int,
+                    optional: true,
+                    default: None,
+                    constraints: vec![PyConstraint::Range {
+                        min: Some(-1.0),
+                        max: None,
+                    }],
+                    doc: None,
+                },
+            ],
+            doc: None,
+            path: PathBuf::from("test_py/test.py"),
+        };
+
+        let token_stream = TokenStream2::from(Form::from(&py_file)).to_string();
+
+        assert!(token_stream.contains(
+            &quote! {
+                struct Form_test {
+                    #[field(validate = py_len(Some(1u64), None))]
+                    #[field(validate = py_len(None, Some(100u64)))]
+                    text: String,
+                    #[field(validate = py_range(Some(-1f64), None))]
+                    top_k: PyOptional<i64>
+                }
+            }
+            .to_string()
+        ));
+        assert!(token_stream.contains(
+            &quote! {
+                if let Some(value) = &top_k {
+                    FieldError::check("top_k", "int | None", py_range(value, Some(-1f64), None), &mut errors);
+                }
+            }
+            .to_string()
+        ));
+    }
+
+    #[test]
+    fn test_negative_int_reaches_python() {
+        use pyo3::conversion::IntoPy;
+        use pyo3::types::IntoPyDict;
+
+        let py_file = PythonFile {
+            file_name: "test.py".into(),
+            file_stem: "test".into(),
+            ident: "test".into(),
+            main_func_args: vec![PyArg {
+                name: "offset".into(),
+                data_type: PyPrimitiveDataType::Int,
+                optional: true,
+                default: Some(PyDefault::Int(-1)),
+                constraints: vec![],
+                doc: None,
+            }],
+            doc: None,
+            path: PathBuf::from("test_py/test.py"),
+        };
+
+        let token_stream: TokenStream2 = Form::from(&py_file).into();
+        assert!(token_stream
+            .to_string()
+            .contains(&quote! { offset: PyOptional<i64> }.to_string()));
+        assert!(token_stream
+            .to_string()
+            .contains(&quote! { self.offset.0.unwrap_or_else(|| -1) }.to_string()));
+
+        pyo3::prepare_freethreaded_python();
+        pyo3::Python::with_gil(|py| {
+            for value in [-42i64, -1, i64::MIN] {
+                let py_any: pyo3::Py<pyo3::PyAny> = value.into_py(py);
+                let kwargs = vec![("offset", py_any)].into_py_dict(py);
+
+                let offset = py.eval("offset", None, Some(kwargs)).unwrap();
+                assert_eq!(offset.extract::<i64>().unwrap(), value);
+                assert_eq!(offset.str().unwrap().to_string(), value.to_string());
+            }
+        });
+    }
 }
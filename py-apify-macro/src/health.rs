@@ -0,0 +1,100 @@
+use crate::TokenStream2;
+use quote::quote;
+
+pub fn gen_health() -> TokenStream2 {
+    quote! {
+        /// Outcome of the import of a Python file at startup.
+        pub struct PyModuleStatus {
+            pub file_stem: &'static str,
+            pub module_name: &'static str,
+            /// `None` if the file was imported, or runs in worker processes
+            pub import_error: Option<String>,
+        }
+
+        /// Import status of every Python file, reported by `/readyz`.
+        pub struct PyModules(pub Vec<PyModuleStatus>);
+
+        /// Calls the optional `ready()` function of a module imported in this
+        /// process. Modules without one are ready once imported.
+        fn py_module_ready(py: pyo3::Python, module_name: &str) -> Result<bool, String> {
+            let module = py.import(module_name).map_err(|e| e.to_string())?;
+
+            if !module.hasattr("ready").map_err(|e| e.to_string())? {
+                return Ok(true);
+            }
+
+            module
+                .getattr("ready")
+                .and_then(|ready| ready.call0())
+                .and_then(|ready| ready.is_true())
+                .map_err(|e| e.to_string())
+        }
+
+        /// Liveness: the process answers requests.
+        #[get("/healthz")]
+        fn healthz() -> rocket::serde::json::Json<rocket::serde::json::Value> {
+            rocket::serde::json::Json(rocket::serde::json::json!({ "status": "ok" }))
+        }
+
+        /// Readiness: every Python file was imported and its `ready()`
+        /// function, if any, returns true. Files running in worker processes
+        /// are ready while one of their workers is running.
+        #[get("/readyz")]
+        async fn readyz(
+            py_modules: &rocket::State<PyModules>,
+            hook_pool: &rocket::State<HookPool>,
+        ) -> (rocket::http::Status, rocket::serde::json::Json<rocket::serde::json::Value>) {
+            let in_process: Vec<&'static str> = py_modules
+                .0
+                .iter()
+                .filter(|module| module.import_error.is_none() && hook_pool.runs_in_process(module.file_stem))
+                .map(|module| module.module_name)
+                .collect();
+
+            let ready_checks: std::collections::HashMap<&'static str, Result<bool, String>> =
+                rocket::tokio::task::spawn_blocking(move || {
+                    pyo3::Python::with_gil(|py| {
+                        in_process
+                            .into_iter()
+                            .map(|module_name| (module_name, py_module_ready(py, module_name)))
+                            .collect()
+                    })
+                })
+                .await
+                .unwrap_or_default();
+
+            let mut all_ready = true;
+            let mut modules = rocket::serde::json::Value::Object(Default::default());
+
+            for module in &py_modules.0 {
+                let (ready, error) = match (&module.import_error, hook_pool.alive_workers(module.file_stem)) {
+                    (Some(import_error), _) => (false, Some(import_error.clone())),
+                    (None, Some(0)) => (false, Some("no worker process is running".to_string())),
+                    (None, Some(_)) => (true, None),
+                    (None, None) => match ready_checks.get(module.module_name) {
+                        Some(Ok(true)) => (true, None),
+                        Some(Ok(false)) => (false, None),
+                        Some(Err(e)) => (false, Some(e.clone())),
+                        None => (false, Some("the readiness check failed".to_string())),
+                    },
+                };
+
+                all_ready &= ready;
+                modules[module.file_stem] = rocket::serde::json::json!({ "ready": ready, "error": error });
+            }
+
+            let status = match all_ready {
+                true => rocket::http::Status::Ok,
+                false => rocket::http::Status::ServiceUnavailable,
+            };
+
+            (
+                status,
+                rocket::serde::json::Json(rocket::serde::json::json!({
+                    "status": if all_ready { "ready" } else { "not ready" },
+                    "modules": modules,
+                })),
+            )
+        }
+    }
+}
@@ -11,7 +11,7 @@ pub struct HookFunctionIdent {
 impl From<&PythonFile> for HookFunctionIdent {
     fn from(python_file: &PythonFile) -> HookFunctionIdent {
         HookFunctionIdent {
-            ident: Ident::new(&format!("hook_{}", python_file.uuid), Span::call_site()),
+            ident: Ident::new(&format!("hook_{}", python_file.ident), Span::call_site()),
         }
     }
 }
@@ -33,7 +33,7 @@ impl From<&PythonFile> for Hook {
     fn from(python_file: &PythonFile) -> Hook {
         Hook {
             ident: HookFunctionIdent::from(python_file),
-            py_module_name: Literal::string(&python_file.uuid),
+            py_module_name: Literal::string(&python_file.module_name()),
             py_file_name: Literal::string(&python_file.file_name),
             form_ident: FormIdent::from(python_file),
         }
@@ -50,18 +50,17 @@ impl From<Hook> for TokenStream2 {
         quote! {
             fn #hook_function_ident(py_lock: pyo3::Python, input: #form_ident) -> Result<String, PyApifyError> {
                 let kwargs : &pyo3::types::PyDict = input.kwargs(py_lock);
+                log::debug!("calling `call` from {} with {}", #file_name, kwargs);
 
-                let nlp = pyo3::types::PyModule::import(
-                    py_lock,
-                    #module_name,
-                )
-                .expect("failed to import PyModule");
+                // a file that failed to import at startup is reported by `/readyz`
+                let nlp = pyo3::types::PyModule::import(py_lock, #module_name)
+                    .map_err(|e| PyApifyError::ModuleNotLoaded(format!("{}: {}", #file_name, e)))?;
 
                 match nlp
                     .getattr("call").map_err(|_e| PyApifyError::HookFunctionNotFound(#file_name.to_string()))?
                     .call((), Some(kwargs)) {
-                        Ok(result) => Ok(result.extract().unwrap_or("{}".to_string())),
-                        Err(e) => Err(PyApifyError::HookFunctionFailure(e.to_string()))
+                        Ok(result) => py_result_to_json(#file_name, result),
+                        Err(e) => Err(PyApifyError::from_py_err(py_lock, e))
                 }
             }
         }
@@ -78,29 +77,28 @@ mod tests {
         let py_file = PythonFile {
             file_name: "test.py".into(),
             file_stem: "test".into(),
-            uuid: "27597466".into(),
+            ident: "test".into(),
             main_func_args: vec![],
+            doc: None,
             path: PathBuf::from("/test.py"),
         };
 
         let token_stream: TokenStream2 = Hook::from(&py_file).into();
 
         let target_ts = quote! {
-            fn hook_27597466(py_lock: pyo3::Python, input: Form_27597466) -> String {
+            fn hook_test(py_lock: pyo3::Python, input: Form_test) -> Result<String, PyApifyError> {
                 let kwargs : &pyo3::types::PyDict = input.kwargs(py_lock);
+                log::debug!("calling `call` from {} with {}", "test.py", kwargs);
 
-                let nlp = pyo3::types::PyModule::import(
-                    py_lock,
-                    "27597466",
-                )
-                .expect("failed to import PyModule");
+                // a file that failed to import at startup is reported by `/readyz`
+                let nlp = pyo3::types::PyModule::import(py_lock, "py_apify_test")
+                    .map_err(|e| PyApifyError::ModuleNotLoaded(format!("{}: {}", "test.py", e)))?;
 
                 match nlp
-                    .getattr("call")
-                    .expect(&format!("`call` function was not found in {}. Your python file must include a `call` function that returns json data:\n\ndef call(input):\n\tjson.dumps('{{'foo': 'bar'}}')\n\n", "test.py"))
+                    .getattr("call").map_err(|_e| PyApifyError::HookFunctionNotFound("test.py".to_string()))?
                     .call((), Some(kwargs)) {
-                        Ok(result) => result.extract().unwrap_or("{}".to_string()),
-                        Err(e) => format!("{{\"error\": \"{}\"}}", e.to_string())
+                        Ok(result) => py_result_to_json("test.py", result),
+                        Err(e) => Err(PyApifyError::from_py_err(py_lock, e))
                 }
             }
         };
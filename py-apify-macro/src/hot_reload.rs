@@ -0,0 +1,147 @@
+use crate::python_file::PythonFile;
+use proc_macro2::{Literal, TokenStream as TokenStream2};
+use quote::quote;
+
+pub fn gen_hot_reload() -> TokenStream2 {
+    quote! {
+        /// A Python file re-imported by `watch_py_files` when it changes.
+        pub struct WatchedFile {
+            pub file_stem: &'static str,
+            pub path: &'static str,
+            pub file_name: &'static str,
+            pub module_name: &'static str,
+            /// Source of the file when the routes were generated
+            pub code: &'static str,
+        }
+
+        /// Reloads a Python file, see `reload_py_module` in py-apify-core.
+        fn reload_py_module(py: pyo3::Python, file: &WatchedFile) -> Result<(), String> {
+            let code = std::fs::read_to_string(file.path).map_err(|e| e.to_string())?;
+
+            py_apify_core::hot_reload::reload_py_module(py, file.code, &code, file.file_name, file.module_name)
+        }
+
+        fn modified_at(path: &str) -> Option<std::time::SystemTime> {
+            std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+        }
+
+        /// Polls the Python files every second and reloads the ones that changed.
+        fn watch_py_files(files: Vec<WatchedFile>) {
+            if files.is_empty() {
+                return;
+            }
+
+            std::thread::spawn(move || {
+                let mut modified: Vec<Option<std::time::SystemTime>> =
+                    files.iter().map(|file| modified_at(file.path)).collect();
+
+                loop {
+                    std::thread::sleep(std::time::Duration::from_secs(1));
+
+                    for (file, modified) in files.iter().zip(modified.iter_mut()) {
+                        let modified_now = modified_at(file.path);
+
+                        if modified_now == *modified {
+                            continue;
+                        }
+
+                        *modified = modified_now;
+
+                        match pyo3::Python::with_gil(|py| reload_py_module(py, file)) {
+                            Ok(()) => log::info!("Reloaded `{}`", file.file_name),
+                            Err(e) => log::error!("Rejected the new version of `{}`: {}", file.file_name, e),
+                        }
+                    }
+                }
+            });
+        }
+    }
+}
+
+/// Generates `watched_py_files`, listing the Python files to watch in
+/// development mode (`hot-reload` feature).
+pub struct HotReload {
+    watched_files: Vec<TokenStream2>,
+}
+
+impl From<&Vec<PythonFile>> for HotReload {
+    fn from(python_files: &Vec<PythonFile>) -> HotReload {
+        HotReload {
+            watched_files: python_files
+                .iter()
+                .map(|python_file| {
+                    let file_stem = Literal::string(&python_file.file_stem);
+                    let path = Literal::string(&python_file.absolute_path());
+                    let file_name = Literal::string(&python_file.file_name);
+                    let module_name = Literal::string(&python_file.module_name());
+
+                    quote! {
+                        WatchedFile {
+                            file_stem: #file_stem,
+                            path: #path,
+                            file_name: #file_name,
+                            module_name: #module_name,
+                            code: include_str!(#path),
+                        }
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<HotReload> for TokenStream2 {
+    fn from(hot_reload: HotReload) -> Self {
+        let watched_files = hot_reload.watched_files;
+
+        quote! {
+            fn watched_py_files() -> Vec<WatchedFile> {
+                vec![#(#watched_files),*]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::py_arg::{PyArg, PyPrimitiveDataType};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_hot_reload() {
+        let py_file = PythonFile {
+            file_name: "test.py".into(),
+            file_stem: "test".into(),
+            ident: "test".into(),
+            main_func_args: vec![PyArg {
+                name: "input".into(),
+                data_type: PyPrimitiveDataType::Str,
+                optional: false,
+                default: None,
+                constraints: vec![],
+                doc: None,
+            }],
+            doc: None,
+            path: PathBuf::from("test_py/test.py"),
+        };
+
+        let full_file_path = Literal::string(&py_file.absolute_path());
+
+        let token_stream: TokenStream2 = HotReload::from(&vec![py_file]).into();
+
+        let target_ts = quote! {
+            fn watched_py_files() -> Vec<WatchedFile> {
+                vec![WatchedFile {
+                    file_stem: "test",
+                    path: #full_file_path,
+                    file_name: "test.py",
+                    module_name: "py_apify_test",
+                    code: include_str!(#full_file_path),
+                }]
+            }
+        };
+
+        assert_eq!(token_stream.to_string(), target_ts.to_string());
+    }
+}
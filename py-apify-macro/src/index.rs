@@ -0,0 +1,31 @@
+use crate::python_file::{index_document, PythonFile};
+use proc_macro2::{Literal, TokenStream as TokenStream2};
+use quote::quote;
+use serde_json::Value;
+
+/// The index served on `/`: the routes of the Python files with the
+/// docstrings of their `call` function.
+pub struct Index {
+    document: Value,
+}
+
+impl From<&Vec<PythonFile>> for Index {
+    fn from(python_files: &Vec<PythonFile>) -> Index {
+        Index {
+            document: index_document(python_files),
+        }
+    }
+}
+
+impl From<Index> for TokenStream2 {
+    fn from(index: Index) -> Self {
+        let document = Literal::string(&index.document.to_string());
+
+        quote! {
+            #[get("/")]
+            fn index_document() -> rocket::response::content::RawJson<&'static str> {
+                rocket::response::content::RawJson(#document)
+            }
+        }
+    }
+}
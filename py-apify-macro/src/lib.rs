@@ -1,43 +1,96 @@
-#![feature(type_ascription)]
-
 extern crate proc_macro;
 use proc_macro::TokenStream;
-use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::quote;
 use syn::{parse::Parser, punctuated::Punctuated, LitStr, Token};
+use std::path::PathBuf;
 
+mod config;
+mod constraint;
 mod error;
 mod file_loader;
 mod form;
+mod health;
 mod hook;
+mod hot_reload;
+mod index;
+mod metrics;
 mod mount;
-mod py_arg;
-mod python_file;
+mod openapi;
+mod optional;
+mod pool;
+mod py_module;
 mod request_handler;
+mod request_id;
+mod result;
+mod upload;
+mod worker;
+
+use py_apify_core::{py_arg, python_file};
 
-use file_loader::PythonFileLoader;
+use file_loader::PythonFileLoaders;
 use form::Form;
 use hook::Hook;
+use hot_reload::HotReload;
+use index::Index;
 use mount::RocketMount;
-use request_handler::RequestHandler;
+use openapi::OpenApi;
+use request_handler::{JsonRequestHandler, RequestHandler};
+use worker::WorkerDispatch;
 
 #[proc_macro]
 pub fn apify(item: TokenStream) -> TokenStream {
     let error = error::gen_error();
+    let optional = optional::gen_optional();
+    let request_id = request_id::gen_request_id();
+    let config = config::gen_config();
+    let pool = pool::gen_pool();
+    let py_module = py_module::gen_py_module();
+    let result = result::gen_result();
+    let worker = worker::gen_worker();
+    let health = health::gen_health();
+    let metrics = metrics::gen_metrics();
+    let hot_reload = if cfg!(feature = "hot-reload") {
+        hot_reload::gen_hot_reload()
+    } else {
+        quote! {}
+    };
 
-    let args: Vec<String> = Punctuated::<LitStr, Token![,]>::parse_terminated
-        .parse(item)
-        .expect("invalid arguments")
-        .into_iter()
-        .map(|e| e.value())
-        .collect();
+    let args: Vec<String> = match Punctuated::<LitStr, Token![,]>::parse_terminated.parse(item) {
+        Ok(args) => args.into_iter().map(|e| e.value()).collect(),
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    // rustc runs in the workspace root, the paths are relative to the crate
+    let base_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into()));
+
+    let python_files = match python_file::get_py_files(args, &base_dir, !cfg!(feature = "no-check")) {
+        Ok(python_files) => python_files,
+        Err(e) => {
+            return syn::Error::new(Span::call_site(), e)
+                .to_compile_error()
+                .into()
+        }
+    };
 
-    let python_files = python_file::get_py_files(args);
+    // only generated when needed, unused types would trigger warnings
+    let upload = if python_files.iter().any(|file| file.has_uploads()) {
+        upload::gen_upload()
+    } else {
+        quote! {}
+    };
 
-    let loaders: Vec<TokenStream2> = python_files
+    let constraint = if python_files
         .iter()
-        .map(|file| PythonFileLoader::from(file).into())
-        .collect();
+        .flat_map(|file| &file.main_func_args)
+        .any(|arg| !arg.constraints.is_empty())
+    {
+        constraint::gen_constraint()
+    } else {
+        quote! {}
+    };
+
+    let loaders: TokenStream2 = PythonFileLoaders::from(&python_files).into();
 
     let hooks: Vec<TokenStream2> = python_files
         .iter()
@@ -49,6 +102,34 @@ pub fn apify(item: TokenStream) -> TokenStream {
         .map(|file| RequestHandler::from(file).into())
         .collect();
 
+    let json_routes: Vec<TokenStream2> = python_files
+        .iter()
+        .filter(|file| !file.has_uploads())
+        .map(|file| JsonRequestHandler::from(file).into())
+        .collect();
+
+    let worker_dispatch: TokenStream2 = WorkerDispatch::from(&python_files).into();
+
+    let (watched_py_files, watch_worker_file) = if cfg!(feature = "hot-reload") {
+        (
+            HotReload::from(&python_files).into(),
+            quote! {
+                watch_py_files(
+                    watched_py_files()
+                        .into_iter()
+                        .filter(|file| file.file_stem == file_stem)
+                        .collect(),
+                );
+            },
+        )
+    } else {
+        (quote! {}, quote! {})
+    };
+
+    let openapi: TokenStream2 = OpenApi::from(&python_files).into();
+
+    let index: TokenStream2 = Index::from(&python_files).into();
+
     let mount: TokenStream2 = RocketMount::from(&python_files).into();
 
     let forms: Vec<TokenStream2> = python_files
@@ -56,19 +137,45 @@ pub fn apify(item: TokenStream) -> TokenStream {
         .map(|file| Form::from(file).into())
         .collect();
 
-    return quote! {
+    quote! {{
+        // the routes are defined in the function calling `apify!`
+        #![allow(non_local_definitions)]
+
         #error
+        #optional
+        #constraint
+        #request_id
+        #config
+        #pool
+        #py_module
+        #result
+        #upload
+        #worker
+        #health
+        #metrics
+        #hot_reload
         use rocket::form::{Form, Strict};
         use pyo3::prelude::*;
 
         pyo3::prepare_freethreaded_python();
         pyo3::Python::with_gil(|py| {
             #(#forms)*
-            #(#loaders)*
+            #loaders
             #(#routes)*
+            #(#json_routes)*
+            #openapi
+            #index
             #(#hooks)*
+            #worker_dispatch
+            #watched_py_files
+
+            if let Ok(file_stem) = std::env::var(PY_APIFY_WORKER_ENV) {
+                #watch_worker_file
+                run_worker(py, &file_stem, load_py_module, call_hook);
+            }
+
             #mount
         })
-    }
-    .into();
+    }}
+    .into()
 }
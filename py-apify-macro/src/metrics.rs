@@ -0,0 +1,199 @@
+use crate::TokenStream2;
+use quote::quote;
+
+pub fn gen_metrics() -> TokenStream2 {
+    quote! {
+        /// Upper bounds, in seconds, of the histogram buckets.
+        const METRICS_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+        struct Histogram {
+            /// Cumulative count of each bucket of `METRICS_BUCKETS`
+            buckets: Vec<std::sync::atomic::AtomicU64>,
+            count: std::sync::atomic::AtomicU64,
+            sum_micros: std::sync::atomic::AtomicU64,
+        }
+
+        impl Histogram {
+            fn new() -> Self {
+                Histogram {
+                    buckets: METRICS_BUCKETS.iter().map(|_| std::sync::atomic::AtomicU64::new(0)).collect(),
+                    count: std::sync::atomic::AtomicU64::new(0),
+                    sum_micros: std::sync::atomic::AtomicU64::new(0),
+                }
+            }
+
+            fn observe(&self, duration: std::time::Duration) {
+                let seconds = duration.as_secs_f64();
+
+                for (bucket, le) in self.buckets.iter().zip(METRICS_BUCKETS) {
+                    if seconds <= *le {
+                        bucket.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    }
+                }
+
+                self.count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                self.sum_micros.fetch_add(duration.as_micros() as u64, std::sync::atomic::Ordering::Relaxed);
+            }
+
+            fn render(&self, out: &mut String, name: &str, file_stem: &str) {
+                use std::fmt::Write;
+
+                let count = self.count.load(std::sync::atomic::Ordering::Relaxed);
+
+                for (bucket, le) in self.buckets.iter().zip(METRICS_BUCKETS) {
+                    let _ = writeln!(
+                        out,
+                        "{}_bucket{{file_stem=\"{}\",le=\"{}\"}} {}",
+                        name,
+                        file_stem,
+                        le,
+                        bucket.load(std::sync::atomic::Ordering::Relaxed)
+                    );
+                }
+
+                let _ = writeln!(out, "{}_bucket{{file_stem=\"{}\",le=\"+Inf\"}} {}", name, file_stem, count);
+                let _ = writeln!(
+                    out,
+                    "{}_sum{{file_stem=\"{}\"}} {}",
+                    name,
+                    file_stem,
+                    self.sum_micros.load(std::sync::atomic::Ordering::Relaxed) as f64 / 1_000_000.0
+                );
+                let _ = writeln!(out, "{}_count{{file_stem=\"{}\"}} {}", name, file_stem, count);
+            }
+        }
+
+        struct RouteMetrics {
+            requests: std::sync::atomic::AtomicU64,
+            /// Error count by `PyApifyError` kind
+            errors: std::sync::Mutex<std::collections::BTreeMap<&'static str, u64>>,
+            latency: Histogram,
+            gil_wait: Histogram,
+            python: Histogram,
+        }
+
+        /// Metrics of the routes of each Python file, served in the Prometheus
+        /// text format by `/metrics`.
+        pub struct Metrics {
+            routes: std::collections::BTreeMap<&'static str, RouteMetrics>,
+        }
+
+        impl Metrics {
+            pub fn new(file_stems: &[&'static str]) -> Self {
+                Metrics {
+                    routes: file_stems
+                        .iter()
+                        .map(|file_stem| {
+                            (
+                                *file_stem,
+                                RouteMetrics {
+                                    requests: std::sync::atomic::AtomicU64::new(0),
+                                    errors: std::sync::Mutex::new(std::collections::BTreeMap::new()),
+                                    latency: Histogram::new(),
+                                    gil_wait: Histogram::new(),
+                                    python: Histogram::new(),
+                                },
+                            )
+                        })
+                        .collect(),
+                }
+            }
+
+            /// Records a request answered by a route, from the reception of
+            /// the request to the response.
+            pub fn observe_request(&self, file_stem: &str, duration: std::time::Duration, error: Option<&PyApifyError>) {
+                if let Some(route) = self.routes.get(file_stem) {
+                    route.requests.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    route.latency.observe(duration);
+
+                    if let Some(error) = error {
+                        *route
+                            .errors
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .entry(error.kind())
+                            .or_insert(0) += 1;
+                    }
+                }
+            }
+
+            /// Records the time a hook waited for the GIL.
+            pub fn observe_gil_wait(&self, file_stem: &str, duration: std::time::Duration) {
+                if let Some(route) = self.routes.get(file_stem) {
+                    route.gil_wait.observe(duration);
+                }
+            }
+
+            /// Records the time spent running a hook, in this process or in a
+            /// worker process.
+            pub fn observe_python(&self, file_stem: &str, duration: std::time::Duration) {
+                if let Some(route) = self.routes.get(file_stem) {
+                    route.python.observe(duration);
+                }
+            }
+
+            pub fn render(&self) -> String {
+                use std::fmt::Write;
+
+                let mut out = String::new();
+
+                out.push_str("# HELP py_apify_requests_total Requests answered by the routes of a Python file.\n");
+                out.push_str("# TYPE py_apify_requests_total counter\n");
+                for (file_stem, route) in &self.routes {
+                    let _ = writeln!(
+                        out,
+                        "py_apify_requests_total{{file_stem=\"{}\"}} {}",
+                        file_stem,
+                        route.requests.load(std::sync::atomic::Ordering::Relaxed)
+                    );
+                }
+
+                out.push_str("# HELP py_apify_errors_total Requests answered with an error, by kind of error.\n");
+                out.push_str("# TYPE py_apify_errors_total counter\n");
+                for (file_stem, route) in &self.routes {
+                    for (error, count) in route.errors.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+                        let _ = writeln!(
+                            out,
+                            "py_apify_errors_total{{file_stem=\"{}\",error=\"{}\"}} {}",
+                            file_stem, error, count
+                        );
+                    }
+                }
+
+                let histograms: [(&str, &str, fn(&RouteMetrics) -> &Histogram); 3] = [
+                    (
+                        "py_apify_request_duration_seconds",
+                        "Time from the reception of a request to its response.",
+                        |route| &route.latency,
+                    ),
+                    (
+                        "py_apify_gil_wait_seconds",
+                        "Time spent by a hook waiting for the GIL.",
+                        |route| &route.gil_wait,
+                    ),
+                    (
+                        "py_apify_python_duration_seconds",
+                        "Time spent running the `call` function.",
+                        |route| &route.python,
+                    ),
+                ];
+
+                for (name, help, histogram) in histograms.iter() {
+                    let _ = writeln!(out, "# HELP {} {}", name, help);
+                    let _ = writeln!(out, "# TYPE {} histogram", name);
+
+                    for (file_stem, route) in &self.routes {
+                        histogram(route).render(&mut out, name, file_stem);
+                    }
+                }
+
+                out
+            }
+        }
+
+        #[get("/metrics")]
+        fn prometheus_metrics(hook_pool: &rocket::State<HookPool>) -> (rocket::http::ContentType, String) {
+            (rocket::http::ContentType::Plain, hook_pool.metrics().render())
+        }
+    }
+}
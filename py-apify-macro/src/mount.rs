@@ -1,41 +1,115 @@
 use crate::python_file::PythonFile;
-use crate::request_handler::{RequestHandlerIdent, RouteAttribute};
+use crate::request_handler::{JsonRequestHandlerIdent, RequestHandlerIdent, RouteAttribute};
 use proc_macro2::{Ident, Literal, TokenStream as TokenStream2};
 use quote::quote;
 
 #[derive(Clone)]
 pub struct RocketMount {
     routes: Vec<RequestHandlerIdent>,
+    json_routes: Vec<JsonRequestHandlerIdent>,
     literal_route: Vec<RouteAttribute>,
+    file_stems: Vec<Literal>,
+    module_names: Vec<Literal>,
+    hot_reload: bool,
 }
 
 impl From<&Vec<PythonFile>> for RocketMount {
     fn from(python_files: &Vec<PythonFile>) -> RocketMount {
         RocketMount {
             routes: python_files.iter().map(|file| file.into()).collect(),
+            json_routes: python_files
+                .iter()
+                .filter(|file| !file.has_uploads())
+                .map(|file| file.into())
+                .collect(),
             literal_route: python_files.iter().map(|file| file.into()).collect(),
+            file_stems: python_files
+                .iter()
+                .map(|file| Literal::string(&file.file_stem))
+                .collect(),
+            module_names: python_files
+                .iter()
+                .map(|file| Literal::string(&file.module_name()))
+                .collect(),
+            hot_reload: cfg!(feature = "hot-reload"),
         }
     }
 }
 
 impl From<RocketMount> for TokenStream2 {
     fn from(rocket_mount: RocketMount) -> Self {
-        let idents = rocket_mount
+        let mut idents = rocket_mount
             .routes
             .clone()
             .into_iter()
             .map(|e| e.into())
             .collect::<Vec<Ident>>();
 
+        idents.extend(
+            rocket_mount
+                .json_routes
+                .clone()
+                .into_iter()
+                .map(|e| e.into())
+                .collect::<Vec<Ident>>(),
+        );
+
         let literals = rocket_mount
             .literal_route
             .into_iter()
             .map(|e| e.into())
             .collect::<Vec<Literal>>();
 
+        let file_stems = rocket_mount.file_stems;
+        let module_names = rocket_mount.module_names;
+
+        let watch_py_files = if rocket_mount.hot_reload {
+            quote! {
+                watch_py_files(
+                    watched_py_files()
+                        .into_iter()
+                        .filter(|file| hook_pool.runs_in_process(file.file_stem))
+                        .collect(),
+                );
+            }
+        } else {
+            quote! {}
+        };
+
         quote! {
-            rocket::build().mount("/", routes![#(#idents),*])
-                #(.register(#literals, catchers![invalid_argument]))*
+            {
+                let figment = rocket::Config::figment();
+                let py_apify_config = PyApifyConfig::from_figment(&figment);
+                let rocket = rocket::custom(py_apify_config.with_upload_limits(figment));
+                let hook_pool = HookPool::new(&py_apify_config, &[#(#file_stems),*]);
+
+                let py_modules = PyModules(
+                    [#((#file_stems, #module_names)),*]
+                        .iter()
+                        .map(|&(file_stem, module_name)| PyModuleStatus {
+                            file_stem,
+                            module_name,
+                            import_error: match hook_pool.runs_in_process(file_stem) {
+                                true => load_py_module(py, file_stem).err().map(|e| {
+                                    log::error!("Failed to load the Python file of `{}`: {}", file_stem, e);
+                                    e.to_string()
+                                }),
+                                false => None,
+                            },
+                        })
+                        .collect(),
+                );
+
+                #watch_py_files
+
+                rocket
+                    .manage(hook_pool)
+                    .manage(py_apify_config)
+                    .manage(py_modules)
+                    .attach(RequestLogger)
+                    .mount("/", routes![#(#idents,)* index_document, openapi_document, healthz, readyz, prometheus_metrics])
+                    #(.register(#literals, catchers![invalid_argument, invalid_body]))*
+            }
         }
     }
 }
@@ -50,23 +124,56 @@ mod tests {
         let py_file_1 = PythonFile {
             file_name: "test.py".into(),
             file_stem: "test".into(),
-            uuid: "27597466".into(),
+            ident: "test".into(),
             main_func_args: vec![],
+            doc: None,
             path: PathBuf::from("/test.py"),
         };
 
         let py_file_2 = PythonFile {
             file_name: "test-1.py".into(),
             file_stem: "test-1".into(),
-            uuid: "41198456".into(),
+            ident: "test_1".into(),
             main_func_args: vec![],
+            doc: None,
             path: PathBuf::from("/test-1.py"),
         };
 
         let token_stream: TokenStream2 = RocketMount::from(&vec![py_file_1, py_file_2]).into();
 
         let target_ts = quote! {
-            rocket::build().mount("/", routes![route_27597466, route_41198456])
+            {
+                let figment = rocket::Config::figment();
+                let py_apify_config = PyApifyConfig::from_figment(&figment);
+                let rocket = rocket::custom(py_apify_config.with_upload_limits(figment));
+                let hook_pool = HookPool::new(&py_apify_config, &["test", "test-1"]);
+
+                let py_modules = PyModules(
+                    [("test", "py_apify_test"), ("test-1", "py_apify_test_1")]
+                        .iter()
+                        .map(|&(file_stem, module_name)| PyModuleStatus {
+                            file_stem,
+                            module_name,
+                            import_error: match hook_pool.runs_in_process(file_stem) {
+                                true => load_py_module(py, file_stem).err().map(|e| {
+                                    log::error!("Failed to load the Python file of `{}`: {}", file_stem, e);
+                                    e.to_string()
+                                }),
+                                false => None,
+                            },
+                        })
+                        .collect(),
+                );
+
+                rocket
+                    .manage(hook_pool)
+                    .manage(py_apify_config)
+                    .manage(py_modules)
+                    .attach(RequestLogger)
+                    .mount("/", routes![route_test, route_test_1, route_json_test, route_json_test_1, index_document, openapi_document, healthz, readyz, prometheus_metrics])
+                    .register("/test", catchers![invalid_argument, invalid_body])
+                    .register("/test-1", catchers![invalid_argument, invalid_body])
+            }
         };
 
         assert_eq!(token_stream.to_string(), target_ts.to_string());
@@ -0,0 +1,345 @@
+use crate::py_arg::{PyArg, PyPrimitiveDataType};
+use crate::python_file::PythonFile;
+use proc_macro2::{Literal, TokenStream as TokenStream2};
+use quote::quote;
+use serde_json::{json, Map, Value};
+
+pub struct OpenApi {
+    document: Value,
+}
+
+/// Schema of a parameter that can't be `null`, optional parameters are left
+/// out of the query string or of the multipart/form-data body instead.
+fn non_nullable_schema(arg: &PyArg) -> Value {
+    let mut schema = arg.json_schema();
+
+    if let Some(schema) = schema.as_object_mut() {
+        schema.remove("nullable");
+    }
+
+    schema
+}
+
+fn query_parameters(args: &[PyArg]) -> Value {
+    args.iter()
+        .map(|arg| {
+            let mut parameter = json!({
+                "name": arg.name,
+                "in": "query",
+                "required": !arg.optional,
+                "schema": non_nullable_schema(arg),
+            });
+
+            // Rocket reads nested fields as `doc[text]=...` or `doc.text=...`
+            if let PyPrimitiveDataType::Struct(_) = arg.data_type {
+                parameter["style"] = json!("deepObject");
+            }
+
+            parameter
+        })
+        .collect()
+}
+
+fn request_body(args: &[PyArg], content_type: &str) -> Value {
+    let properties: Map<String, Value> = args
+        .iter()
+        .map(|arg| match content_type {
+            "application/json" => (arg.name.clone(), arg.json_schema()),
+            _ => (arg.name.clone(), non_nullable_schema(arg)),
+        })
+        .collect();
+
+    let required: Vec<&str> = args
+        .iter()
+        .filter(|arg| !arg.optional)
+        .map(|arg| arg.name.as_ref())
+        .collect();
+
+    json!({
+        "required": true,
+        "content": {
+            content_type: {
+                "schema": {
+                    "type": "object",
+                    "properties": properties,
+                    "required": required,
+                    "additionalProperties": false,
+                }
+            }
+        }
+    })
+}
+
+fn responses() -> Value {
+    json!({
+        "200": {
+            "description": "The value returned by the `call` function, serialized to JSON unless it is already a string",
+            "content": { "application/json": { "schema": {} } }
+        },
+        "400": { "$ref": "#/components/responses/InvalidArguments" },
+        "422": { "$ref": "#/components/responses/UnprocessableEntity" },
+        "500": { "$ref": "#/components/responses/HookFunctionError" },
+        "503": { "$ref": "#/components/responses/ServiceUnavailable" },
+    })
+}
+
+fn components() -> Value {
+    json!({
+        "schemas": {
+            "Problem": {
+                "description": "RFC 7807 problem details, `exception` is the type of the Python exception and `fields` the invalid fields, if any",
+                "type": "object",
+                "properties": {
+                    "type": { "type": "string", "format": "uri" },
+                    "title": { "type": "string" },
+                    "status": { "type": "integer" },
+                    "detail": { "type": "string" },
+                    "instance": { "type": "string", "format": "uri" },
+                    "exception": { "type": "string" },
+                    "fields": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "name": { "type": "string" },
+                                "expected": { "type": "string", "nullable": true },
+                                "reason": { "type": "string" },
+                            },
+                            "required": ["name", "reason"],
+                        }
+                    },
+                },
+                "required": ["type", "title", "status", "detail", "instance"],
+            }
+        },
+        "responses": {
+            "InvalidArguments": {
+                "description": "`InvalidArguments`: the request does not match the `call` signature",
+                "content": {
+                    "application/problem+json": {
+                        "schema": { "$ref": "#/components/schemas/Problem" }
+                    }
+                }
+            },
+            "ServiceUnavailable": {
+                "description": "`Overloaded`: too many requests are waiting for a Python worker. `ModuleNotLoaded`: the Python file failed to import at startup",
+                "content": {
+                    "application/problem+json": {
+                        "schema": { "$ref": "#/components/schemas/Problem" }
+                    }
+                }
+            },
+            "UnprocessableEntity": {
+                "description": "`InvalidFields`: some fields don't match the `call` signature, they are listed in `fields`. `PythonException`: the `call` function raised a `ValueError`, or a `py_apify.HTTPError` with this status",
+                "content": {
+                    "application/problem+json": {
+                        "schema": { "$ref": "#/components/schemas/Problem" }
+                    }
+                }
+            },
+            "HookFunctionError": {
+                "description": "`HookFunctionNotFound`: the Python file has no `call` function. `HookFunctionFailure`: the `call` function raised an exception. `InvalidReturnValue`: the `call` function returned a value that can't be converted to JSON",
+                "content": {
+                    "application/problem+json": {
+                        "schema": { "$ref": "#/components/schemas/Problem" }
+                    }
+                }
+            }
+        }
+    })
+}
+
+impl From<&Vec<PythonFile>> for OpenApi {
+    fn from(python_files: &Vec<PythonFile>) -> OpenApi {
+        let paths: Map<String, Value> = python_files
+            .iter()
+            .map(|file| {
+                if file.has_uploads() {
+                    let mut operation = json!({
+                        "post": {
+                            "operationId": format!("{}_post", file.file_stem),
+                            "summary": format!("Call `{}` with a multipart/form-data body", file.file_name),
+                            "requestBody": request_body(&file.main_func_args, "multipart/form-data"),
+                            "responses": responses(),
+                        }
+                    });
+
+                    if let Some(doc) = &file.doc {
+                        operation["post"]["description"] = json!(doc);
+                    }
+
+                    return (format!("/{}", file.file_stem), operation);
+                }
+
+                let mut operation = json!({
+                    "get": {
+                        "operationId": format!("{}_get", file.file_stem),
+                        "summary": format!("Call `{}` with query parameters", file.file_name),
+                        "parameters": query_parameters(&file.main_func_args),
+                        "responses": responses(),
+                    },
+                    "post": {
+                        "operationId": format!("{}_post", file.file_stem),
+                        "summary": format!("Call `{}` with a JSON body", file.file_name),
+                        "requestBody": request_body(&file.main_func_args, "application/json"),
+                        "responses": responses(),
+                    }
+                });
+
+                if let Some(doc) = &file.doc {
+                    operation["get"]["description"] = json!(doc);
+                    operation["post"]["description"] = json!(doc);
+                }
+
+                (format!("/{}", file.file_stem), operation)
+            })
+            .collect();
+
+        OpenApi {
+            document: json!({
+                "openapi": "3.0.3",
+                "info": {
+                    "title": std::env::var("CARGO_PKG_NAME").unwrap_or_else(|_| "py-apify".into()),
+                    "version": std::env::var("CARGO_PKG_VERSION").unwrap_or_else(|_| "0.0.0".into()),
+                },
+                "paths": paths,
+                "components": components(),
+            }),
+        }
+    }
+}
+
+impl From<OpenApi> for TokenStream2 {
+    fn from(openapi: OpenApi) -> Self {
+        let document = Literal::string(&openapi.document.to_string());
+
+        quote! {
+            #[get("/openapi.json")]
+            fn openapi_document() -> rocket::response::content::RawJson<&'static str> {
+                rocket::response::content::RawJson(#document)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::py_arg::{PyDefault, PyPrimitiveDataType};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_openapi() {
+        let py_file = PythonFile {
+            file_name: "test.py".into(),
+            file_stem: "test".into(),
+            ident: "test".into(),
+            main_func_args: vec![
+                PyArg {
+                    name: "input".into(),
+                    data_type: PyPrimitiveDataType::Str,
+                    optional: false,
+                    default: None,
+                    constraints: vec![],
+                    doc: Some("The text to tag.".into()),
+                },
+                PyArg {
+                    name: "score".into(),
+                    data_type: PyPrimitiveDataType::Int,
+                    optional: true,
+                    default: Some(PyDefault::Int(5)),
+                    constraints: vec![],
+                    doc: None,
+                },
+            ],
+            doc: Some("Tags a text.".into()),
+            path: PathBuf::from("test_py/test.py"),
+        };
+
+        let openapi = OpenApi::from(&vec![py_file]);
+        let operations = &openapi.document["paths"]["/test"];
+
+        assert_eq!(
+            operations["get"]["parameters"],
+            json!([
+                { "name": "input", "in": "query", "required": true, "schema": { "type": "string", "description": "The text to tag." } },
+                { "name": "score", "in": "query", "required": false, "schema": { "type": "integer", "format": "int64", "default": 5 } },
+            ])
+        );
+
+        let body_schema =
+            &operations["post"]["requestBody"]["content"]["application/json"]["schema"];
+        assert_eq!(body_schema["required"], json!(["input"]));
+        assert_eq!(
+            body_schema["properties"]["score"],
+            json!({ "type": "integer", "format": "int64", "default": 5, "nullable": true })
+        );
+        assert_eq!(operations["get"]["description"], "Tags a text.");
+        assert_eq!(operations["post"]["description"], "Tags a text.");
+        assert_eq!(
+            operations["get"]["responses"]["400"]["$ref"],
+            "#/components/responses/InvalidArguments"
+        );
+    }
+
+    #[test]
+    fn test_openapi_nullable() {
+        let py_file = PythonFile {
+            file_name: "typed.py".into(),
+            file_stem: "typed".into(),
+            ident: "typed".into(),
+            main_func_args: vec![PyArg {
+                name: "threshold".into(),
+                data_type: PyPrimitiveDataType::Float,
+                optional: true,
+                default: Some(PyDefault::None),
+                constraints: vec![],
+                doc: None,
+            }],
+            doc: None,
+            path: PathBuf::from("test_py/typed.py"),
+        };
+
+        let openapi = OpenApi::from(&vec![py_file]);
+        let operations = &openapi.document["paths"]["/typed"];
+
+        assert_eq!(
+            operations["post"]["requestBody"]["content"]["application/json"]["schema"]
+                ["properties"]["threshold"],
+            json!({ "type": "number", "format": "double", "nullable": true })
+        );
+        assert_eq!(
+            operations["get"]["parameters"][0]["schema"],
+            json!({ "type": "number", "format": "double" })
+        );
+    }
+
+    #[test]
+    fn test_openapi_upload() {
+        let py_file = PythonFile {
+            file_name: "ocr.py".into(),
+            file_stem: "ocr".into(),
+            ident: "ocr".into(),
+            main_func_args: vec![PyArg {
+                name: "scan".into(),
+                data_type: PyPrimitiveDataType::UploadFile,
+                optional: false,
+                default: None,
+                constraints: vec![],
+                doc: None,
+            }],
+            doc: None,
+            path: PathBuf::from("test_py/ocr.py"),
+        };
+
+        let openapi = OpenApi::from(&vec![py_file]);
+        let operations = &openapi.document["paths"]["/ocr"];
+
+        assert!(operations.get("get").is_none());
+        assert_eq!(
+            operations["post"]["requestBody"]["content"]["multipart/form-data"]["schema"]
+                ["properties"]["scan"],
+            json!({ "type": "string", "format": "binary" })
+        );
+    }
+}
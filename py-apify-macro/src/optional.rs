@@ -0,0 +1,40 @@
+use crate::TokenStream2;
+use quote::quote;
+
+pub fn gen_optional() -> TokenStream2 {
+    quote! {
+        /// Field of an optional parameter. Rocket parses `Option<T>` leniently,
+        /// a value that doesn't parse would silently become `None`: this field
+        /// is `None` only when it's missing and keeps the errors of `T`.
+        #[derive(rocket::serde::Serialize, rocket::serde::Deserialize)]
+        #[serde(crate = "rocket::serde", transparent)]
+        struct PyOptional<T>(Option<T>);
+
+        #[rocket::async_trait]
+        impl<'v, T: rocket::form::FromForm<'v>> rocket::form::FromForm<'v> for PyOptional<T> {
+            /// Context of `T` and whether a value was pushed
+            type Context = (T::Context, bool);
+
+            fn init(opts: rocket::form::Options) -> Self::Context {
+                (T::init(opts), false)
+            }
+
+            fn push_value(ctxt: &mut Self::Context, field: rocket::form::ValueField<'v>) {
+                ctxt.1 = true;
+                T::push_value(&mut ctxt.0, field)
+            }
+
+            async fn push_data(ctxt: &mut Self::Context, field: rocket::form::DataField<'v, '_>) {
+                ctxt.1 = true;
+                T::push_data(&mut ctxt.0, field).await
+            }
+
+            fn finalize((ctxt, pushed): Self::Context) -> rocket::form::Result<'v, Self> {
+                match pushed {
+                    true => T::finalize(ctxt).map(|value| PyOptional(Some(value))),
+                    false => Ok(PyOptional(None)),
+                }
+            }
+        }
+    }
+}
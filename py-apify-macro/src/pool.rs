@@ -0,0 +1,144 @@
+use crate::TokenStream2;
+use quote::quote;
+
+pub fn gen_pool() -> TokenStream2 {
+    quote! {
+        /// Runs the Python hooks on the blocking thread pool, off the async
+        /// executor. At most `blocking_workers` hooks run at the same time in
+        /// this process, files with worker processes are sent to their
+        /// `ProcessPool`.
+        pub struct HookPool {
+            permits: std::sync::Arc<rocket::tokio::sync::Semaphore>,
+            process_pools: std::collections::HashMap<&'static str, std::sync::Arc<ProcessPool>>,
+            in_flight: std::sync::atomic::AtomicUsize,
+            max_in_flight: usize,
+            metrics: Metrics,
+        }
+
+        struct InFlightGuard<'a>(&'a std::sync::atomic::AtomicUsize);
+
+        impl<'a> Drop for InFlightGuard<'a> {
+            fn drop(&mut self) {
+                self.0.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+            }
+        }
+
+        impl HookPool {
+            pub fn new(config: &PyApifyConfig, file_stems: &[&'static str]) -> Self {
+                let workers = config.blocking_workers.max(1);
+
+                let process_pools = file_stems
+                    .iter()
+                    .filter(|file_stem| config.process_workers(file_stem) > 0)
+                    .map(|file_stem| {
+                        let size = config.process_workers(file_stem);
+                        log::info!("Starting {} worker processes for `{}`", size, file_stem);
+
+                        (*file_stem, std::sync::Arc::new(ProcessPool::new(file_stem, size)))
+                    })
+                    .collect();
+
+                HookPool {
+                    permits: std::sync::Arc::new(rocket::tokio::sync::Semaphore::new(workers)),
+                    process_pools,
+                    in_flight: std::sync::atomic::AtomicUsize::new(0),
+                    max_in_flight: workers + config.queue_depth,
+                    metrics: Metrics::new(file_stems),
+                }
+            }
+
+            pub fn metrics(&self) -> &Metrics {
+                &self.metrics
+            }
+
+            /// Returns `true` if the hook of `file_stem` runs in this process.
+            pub fn runs_in_process(&self, file_stem: &str) -> bool {
+                !self.process_pools.contains_key(file_stem)
+            }
+
+            /// Number of running worker processes of `file_stem`, `None` if the
+            /// hook runs in this process.
+            pub fn alive_workers(&self, file_stem: &str) -> Option<usize> {
+                self.process_pools.get(file_stem).map(|process_pool| process_pool.alive_workers())
+            }
+
+            /// Waits for a free worker and runs the hook of `file_stem`. Fails
+            /// with `PyApifyError::Overloaded` when the queue is full. The
+            /// request id is returned by `py_apify.request_id()` while the hook
+            /// runs.
+            pub async fn call<I>(
+                &self,
+                file_stem: &'static str,
+                request_id: &RequestId,
+                input: I,
+                hook: fn(pyo3::Python, I) -> Result<String, PyApifyError>,
+            ) -> Result<String, PyApifyError>
+            where
+                I: rocket::serde::Serialize + Send + 'static,
+            {
+                let in_flight = self.in_flight.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let _guard = InFlightGuard(&self.in_flight);
+
+                if in_flight >= self.max_in_flight {
+                    return Err(PyApifyError::Overloaded);
+                }
+
+                let result = match self.process_pools.get(file_stem) {
+                    Some(process_pool) => {
+                        let process_pool = process_pool.clone();
+                        let input = rocket::serde::json::to_string(&WorkerRequest {
+                            request_id: request_id.0.clone(),
+                            input,
+                        })
+                        .map_err(|e| PyApifyError::WorkerFailure(e.to_string()))?;
+
+                        rocket::tokio::task::spawn_blocking(move || {
+                            let started_at = std::time::Instant::now();
+                            let result = process_pool.call(&input);
+
+                            (result, None, started_at.elapsed())
+                        })
+                        .await
+                    }
+                    None => {
+                        let permit = self
+                            .permits
+                            .clone()
+                            .acquire_owned()
+                            .await
+                            .map_err(|_| PyApifyError::Overloaded)?;
+
+                        let request_id = request_id.0.clone();
+
+                        rocket::tokio::task::spawn_blocking(move || {
+                            let _permit = permit;
+                            let waiting_since = std::time::Instant::now();
+
+                            pyo3::Python::with_gil(|py| {
+                                let gil_wait = waiting_since.elapsed();
+                                let started_at = std::time::Instant::now();
+
+                                set_py_request_id(py, Some(&request_id));
+                                let result = hook(py, input);
+                                set_py_request_id(py, None);
+
+                                (result, Some(gil_wait), started_at.elapsed())
+                            })
+                        })
+                        .await
+                    }
+                };
+
+                let (result, gil_wait, python_duration) =
+                    result.map_err(|e| PyApifyError::WorkerFailure(e.to_string()))?;
+
+                if let Some(gil_wait) = gil_wait {
+                    self.metrics.observe_gil_wait(file_stem, gil_wait);
+                }
+                self.metrics.observe_python(file_stem, python_duration);
+
+                result
+            }
+        }
+    }
+}
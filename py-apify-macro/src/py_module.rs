@@ -0,0 +1,44 @@
+use crate::TokenStream2;
+use proc_macro2::Literal;
+use py_apify_core::py_module::PY_APIFY_MODULE_NAME;
+use quote::quote;
+
+pub fn gen_py_module() -> TokenStream2 {
+    let module_name = Literal::string(PY_APIFY_MODULE_NAME);
+
+    quote! {
+        /// Imports the `py_apify` helpers module, defining it on first use, see
+        /// `define_py_apify_module` in py-apify-core.
+        fn import_py_apify_module(py: pyo3::Python) -> pyo3::PyResult<&pyo3::types::PyModule> {
+            let sys_modules: &pyo3::types::PyDict = py.import("sys")?.getattr("modules")?.downcast()?;
+
+            if sys_modules.contains(#module_name)? {
+                return py.import(#module_name);
+            }
+
+            py_apify_core::py_module::define_py_apify_module(py)
+        }
+
+        /// Sets the id returned by `py_apify.request_id()` in this thread.
+        fn set_py_request_id(py: pyo3::Python, request_id: Option<&str>) {
+            let result = import_py_apify_module(py)
+                .and_then(|module| module.call_method1("_set_request_id", (request_id,)));
+
+            if let Err(e) = result {
+                log::warn!("Failed to set the request id in Python: {}", e);
+            }
+        }
+
+        impl PyApifyError {
+            /// Maps an exception raised by a hook, see `PyException::from_py_err`
+            /// in py-apify-core.
+            fn from_py_err(py: pyo3::Python, e: pyo3::PyErr) -> Self {
+                match py_apify_core::py_module::PyException::from_py_err(py, import_py_apify_module(py).ok(), &e) {
+                    py_apify_core::py_module::PyException::Rejected { status, exception, detail } =>
+                        PyApifyError::PythonException { status, exception, detail },
+                    py_apify_core::py_module::PyException::Failure(e) => PyApifyError::HookFunctionFailure(e),
+                }
+            }
+        }
+    }
+}
@@ -5,21 +5,33 @@ use quote::quote;
 
 use crate::form::FormIdent;
 
+/// Route of a Python file: `GET` with query parameters, or multipart/form-data
+/// `POST` when `call` takes uploaded files.
 #[derive(Clone)]
 pub struct RouteAttribute {
     route_name: String,
+    multipart: bool,
 }
 
 impl From<&PythonFile> for RouteAttribute {
     fn from(python_file: &PythonFile) -> RouteAttribute {
         RouteAttribute {
             route_name: python_file.file_stem.clone(),
+            multipart: python_file.has_uploads(),
         }
     }
 }
 
 impl From<RouteAttribute> for TokenStream2 {
     fn from(route_attribute: RouteAttribute) -> Self {
+        if route_attribute.multipart {
+            let route_attribute = Literal::string(&format!("/{}", route_attribute.route_name));
+
+            return quote! {
+                #[post(#route_attribute, data = "<form>")]
+            };
+        }
+
         let route_attribute =
             Literal::string(&format!("/{}?<query..>", route_attribute.route_name));
 
@@ -43,7 +55,7 @@ pub struct RequestHandlerIdent {
 impl From<&PythonFile> for RequestHandlerIdent {
     fn from(python_file: &PythonFile) -> RequestHandlerIdent {
         RequestHandlerIdent {
-            ident: Ident::new(&format!("route_{}", python_file.uuid), Span::call_site()),
+            ident: Ident::new(&format!("route_{}", python_file.ident), Span::call_site()),
         }
     }
 }
@@ -56,6 +68,7 @@ impl From<RequestHandlerIdent> for Ident {
 
 pub struct RequestHandler {
     ident: RequestHandlerIdent,
+    file_stem: Literal,
     route_attribute: RouteAttribute,
     hook_function_ident: HookFunctionIdent,
     form_ident: FormIdent,
@@ -65,6 +78,7 @@ impl From<&PythonFile> for RequestHandler {
     fn from(python_file: &PythonFile) -> RequestHandler {
         RequestHandler {
             ident: python_file.into(),
+            file_stem: Literal::string(&python_file.file_stem),
             route_attribute: RouteAttribute::from(python_file),
             hook_function_ident: python_file.into(),
             form_ident: FormIdent::from(python_file),
@@ -74,18 +88,151 @@ impl From<&PythonFile> for RequestHandler {
 
 impl From<RequestHandler> for TokenStream2 {
     fn from(request_handler: RequestHandler) -> Self {
+        let multipart = request_handler.route_attribute.multipart;
         let route_attribute: TokenStream2 = request_handler.route_attribute.into();
         let route_ident: Ident = request_handler.ident.into();
+        let file_stem = request_handler.file_stem;
         let hook_function_ident: Ident = request_handler.hook_function_ident.into();
         let form_ident: Ident = request_handler.form_ident.into();
 
+        let (fields, input) = if multipart {
+            (
+                quote! { form: Result<rocket::form::Form<rocket::form::Strict<#form_ident>>, rocket::form::Errors<'_>> },
+                quote! {
+                    form
+                        .map_err(|errors| PyApifyError::InvalidFields(#form_ident::field_errors(errors)))?
+                        .into_inner()
+                        .into_inner()
+                },
+            )
+        } else {
+            (
+                quote! { query: Result<rocket::form::Strict<#form_ident>, rocket::form::Errors<'_>> },
+                quote! {
+                    query
+                        .map_err(|errors| PyApifyError::InvalidFields(#form_ident::field_errors(errors)))?
+                        .into_inner()
+                },
+            )
+        };
+
         quote! {
             #route_attribute
-            fn #route_ident(query: rocket::form::Strict<#form_ident>) -> Result<rocket::response::content::Json<String>, PyApifyError> {
-                Ok(rocket::response::content::Json(format!(
-                    "{}",
-                    #hook_function_ident(pyo3::Python::acquire_gil().python(), query.into_inner())?
-                )))
+            async fn #route_ident(#fields, hook_pool: &rocket::State<HookPool>, request_id: &RequestId) -> Result<rocket::response::content::RawJson<String>, PyApifyError> {
+                let started_at = std::time::Instant::now();
+
+                let result: Result<rocket::response::content::RawJson<String>, PyApifyError> = async {
+                    let input = #input;
+
+                    Ok(rocket::response::content::RawJson(
+                        hook_pool.call(#file_stem, request_id, input, #hook_function_ident).await?
+                    ))
+                }
+                .await;
+
+                hook_pool.metrics().observe_request(#file_stem, started_at.elapsed(), result.as_ref().err());
+
+                result
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct JsonRouteAttribute {
+    route_name: String,
+}
+
+impl From<&PythonFile> for JsonRouteAttribute {
+    fn from(python_file: &PythonFile) -> JsonRouteAttribute {
+        JsonRouteAttribute {
+            route_name: python_file.file_stem.clone(),
+        }
+    }
+}
+
+impl From<JsonRouteAttribute> for TokenStream2 {
+    fn from(json_route_attribute: JsonRouteAttribute) -> Self {
+        let route_attribute = Literal::string(&format!("/{}", json_route_attribute.route_name));
+
+        quote! {
+            #[post(#route_attribute, format = "json", data = "<body>")]
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct JsonRequestHandlerIdent {
+    ident: Ident,
+}
+
+impl From<&PythonFile> for JsonRequestHandlerIdent {
+    fn from(python_file: &PythonFile) -> JsonRequestHandlerIdent {
+        JsonRequestHandlerIdent {
+            ident: Ident::new(
+                &format!("route_json_{}", python_file.ident),
+                Span::call_site(),
+            ),
+        }
+    }
+}
+
+impl From<JsonRequestHandlerIdent> for Ident {
+    fn from(json_request_handler: JsonRequestHandlerIdent) -> Self {
+        json_request_handler.ident
+    }
+}
+
+pub struct JsonRequestHandler {
+    ident: JsonRequestHandlerIdent,
+    file_stem: Literal,
+    route_attribute: JsonRouteAttribute,
+    hook_function_ident: HookFunctionIdent,
+    form_ident: FormIdent,
+}
+
+impl From<&PythonFile> for JsonRequestHandler {
+    fn from(python_file: &PythonFile) -> JsonRequestHandler {
+        JsonRequestHandler {
+            ident: python_file.into(),
+            file_stem: Literal::string(&python_file.file_stem),
+            route_attribute: JsonRouteAttribute::from(python_file),
+            hook_function_ident: python_file.into(),
+            form_ident: FormIdent::from(python_file),
+        }
+    }
+}
+
+impl From<JsonRequestHandler> for TokenStream2 {
+    fn from(json_request_handler: JsonRequestHandler) -> Self {
+        let route_attribute: TokenStream2 = json_request_handler.route_attribute.into();
+        let route_ident: Ident = json_request_handler.ident.into();
+        let file_stem = json_request_handler.file_stem;
+        let hook_function_ident: Ident = json_request_handler.hook_function_ident.into();
+        let form_ident: Ident = json_request_handler.form_ident.into();
+
+        quote! {
+            #route_attribute
+            async fn #route_ident(body: Result<rocket::serde::json::Json<rocket::serde::json::Value>, rocket::serde::json::Error<'_>>, hook_pool: &rocket::State<HookPool>, request_id: &RequestId) -> Result<rocket::response::content::RawJson<String>, PyApifyError> {
+                let started_at = std::time::Instant::now();
+
+                let result: Result<rocket::response::content::RawJson<String>, PyApifyError> = async {
+                    let body = body.map_err(|e| PyApifyError::InvalidFields(vec![FieldError {
+                        name: String::new(),
+                        expected: Some("object".to_string()),
+                        reason: e.to_string(),
+                    }]))?;
+                    let input = #form_ident::from_json(body.into_inner()).map_err(PyApifyError::InvalidFields)?;
+
+                    Ok(rocket::response::content::RawJson(
+                        hook_pool.call(#file_stem, request_id, input, #hook_function_ident).await?
+                    ))
+                }
+                .await;
+
+                hook_pool.metrics().observe_request(#file_stem, started_at.elapsed(), result.as_ref().err());
+
+                result
             }
         }
     }
@@ -94,6 +241,7 @@ impl From<RequestHandler> for TokenStream2 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::py_arg::{PyArg, PyPrimitiveDataType};
     use std::path::PathBuf;
 
     #[test]
@@ -101,8 +249,9 @@ mod tests {
         let py_file = PythonFile {
             file_name: "test.py".into(),
             file_stem: "test".into(),
-            uuid: "27597466".into(),
+            ident: "test".into(),
             main_func_args: vec![],
+            doc: None,
             path: PathBuf::from("/test.py"),
         };
 
@@ -120,8 +269,9 @@ mod tests {
         let py_file = PythonFile {
             file_name: "test.py".into(),
             file_stem: "test".into(),
-            uuid: "27597466".into(),
+            ident: "test".into(),
             main_func_args: vec![],
+            doc: None,
             path: PathBuf::from("/test.py"),
         };
 
@@ -129,11 +279,130 @@ mod tests {
 
         let target_ts = quote! {
             #[get("/test?<query..>")]
-            fn route_27597466(query: Form_27597466) -> rocket::response::content::Json<String> {
-                return rocket::response::content::Json(format!(
-                    "{}",
-                    hook_27597466(pyo3::Python::acquire_gil().python(), query)
-                ));
+            async fn route_test(query: Result<rocket::form::Strict<Form_test>, rocket::form::Errors<'_>>, hook_pool: &rocket::State<HookPool>, request_id: &RequestId) -> Result<rocket::response::content::RawJson<String>, PyApifyError> {
+                let started_at = std::time::Instant::now();
+
+                let result: Result<rocket::response::content::RawJson<String>, PyApifyError> = async {
+                    let input = query
+                        .map_err(|errors| PyApifyError::InvalidFields(Form_test::field_errors(errors)))?
+                        .into_inner();
+
+                    Ok(rocket::response::content::RawJson(
+                        hook_pool.call("test", request_id, input, hook_test).await?
+                    ))
+                }
+                .await;
+
+                hook_pool.metrics().observe_request("test", started_at.elapsed(), result.as_ref().err());
+
+                result
+            }
+        };
+
+        assert_eq!(token_stream.to_string(), target_ts.to_string());
+    }
+
+    #[test]
+    fn test_multipart_route_handler() {
+        let py_file = PythonFile {
+            file_name: "ocr.py".into(),
+            file_stem: "ocr".into(),
+            ident: "ocr".into(),
+            main_func_args: vec![PyArg {
+                name: "scan".into(),
+                data_type: PyPrimitiveDataType::UploadFile,
+                optional: false,
+                default: None,
+                constraints: vec![],
+                doc: None,
+            }],
+            doc: None,
+            path: PathBuf::from("/ocr.py"),
+        };
+
+        let token_stream: TokenStream2 = RequestHandler::from(&py_file).into();
+
+        let target_ts = quote! {
+            #[post("/ocr", data = "<form>")]
+            async fn route_ocr(form: Result<rocket::form::Form<rocket::form::Strict<Form_ocr>>, rocket::form::Errors<'_>>, hook_pool: &rocket::State<HookPool>, request_id: &RequestId) -> Result<rocket::response::content::RawJson<String>, PyApifyError> {
+                let started_at = std::time::Instant::now();
+
+                let result: Result<rocket::response::content::RawJson<String>, PyApifyError> = async {
+                    let input = form
+                        .map_err(|errors| PyApifyError::InvalidFields(Form_ocr::field_errors(errors)))?
+                        .into_inner()
+                        .into_inner();
+
+                    Ok(rocket::response::content::RawJson(
+                        hook_pool.call("ocr", request_id, input, hook_ocr).await?
+                    ))
+                }
+                .await;
+
+                hook_pool.metrics().observe_request("ocr", started_at.elapsed(), result.as_ref().err());
+
+                result
+            }
+        };
+
+        assert_eq!(token_stream.to_string(), target_ts.to_string());
+    }
+
+    #[test]
+    fn test_json_route_attribute() {
+        let py_file = PythonFile {
+            file_name: "test.py".into(),
+            file_stem: "test".into(),
+            ident: "test".into(),
+            main_func_args: vec![],
+            doc: None,
+            path: PathBuf::from("/test.py"),
+        };
+
+        let token_stream: TokenStream2 = JsonRouteAttribute::from(&py_file).into();
+
+        let target_ts = quote! {
+            #[post("/test", format = "json", data = "<body>")]
+        };
+
+        assert_eq!(token_stream.to_string(), target_ts.to_string());
+    }
+
+    #[test]
+    fn test_json_route_handler() {
+        let py_file = PythonFile {
+            file_name: "test.py".into(),
+            file_stem: "test".into(),
+            ident: "test".into(),
+            main_func_args: vec![],
+            doc: None,
+            path: PathBuf::from("/test.py"),
+        };
+
+        let token_stream: TokenStream2 = JsonRequestHandler::from(&py_file).into();
+
+        let target_ts = quote! {
+            #[post("/test", format = "json", data = "<body>")]
+            async fn route_json_test(body: Result<rocket::serde::json::Json<rocket::serde::json::Value>, rocket::serde::json::Error<'_>>, hook_pool: &rocket::State<HookPool>, request_id: &RequestId) -> Result<rocket::response::content::RawJson<String>, PyApifyError> {
+                let started_at = std::time::Instant::now();
+
+                let result: Result<rocket::response::content::RawJson<String>, PyApifyError> = async {
+                    let body = body.map_err(|e| PyApifyError::InvalidFields(vec![FieldError {
+                        name: String::new(),
+                        expected: Some("object".to_string()),
+                        reason: e.to_string(),
+                    }]))?;
+                    let input = Form_test::from_json(body.into_inner()).map_err(PyApifyError::InvalidFields)?;
+
+                    Ok(rocket::response::content::RawJson(
+                        hook_pool.call("test", request_id, input, hook_test).await?
+                    ))
+                }
+                .await;
+
+                hook_pool.metrics().observe_request("test", started_at.elapsed(), result.as_ref().err());
+
+                result
             }
         };
 
@@ -0,0 +1,79 @@
+use crate::TokenStream2;
+use quote::quote;
+
+pub fn gen_request_id() -> TokenStream2 {
+    quote! {
+        /// Identifier of a request, generated on first use and cached for the
+        /// rest of the request.
+        #[derive(Debug, Clone)]
+        pub struct RequestId(pub String);
+
+        impl RequestId {
+            pub fn of<'r>(req: &'r rocket::Request<'_>) -> &'r RequestId {
+                req.local_cache(|| {
+                    req.headers()
+                        .get_one(py_apify_core::problem::REQUEST_ID_HEADER)
+                        .filter(|id| py_apify_core::problem::is_valid_request_id(id))
+                        .map(|id| RequestId(id.to_string()))
+                        .unwrap_or_else(|| RequestId(py_apify_core::problem::generate_request_id()))
+                })
+            }
+        }
+
+        #[rocket::async_trait]
+        impl<'r> rocket::request::FromRequest<'r> for &'r RequestId {
+            type Error = std::convert::Infallible;
+
+            async fn from_request(req: &'r rocket::Request<'_>) -> rocket::request::Outcome<Self, Self::Error> {
+                rocket::request::Outcome::Success(RequestId::of(req))
+            }
+        }
+
+        /// Reception time of a request.
+        struct RequestStart(std::time::Instant);
+
+        /// Logs each request on entry and exit with its id, and returns the id
+        /// in the `X-Request-Id` header.
+        pub struct RequestLogger;
+
+        #[rocket::async_trait]
+        impl rocket::fairing::Fairing for RequestLogger {
+            fn info(&self) -> rocket::fairing::Info {
+                rocket::fairing::Info {
+                    name: "Request logger",
+                    kind: rocket::fairing::Kind::Request | rocket::fairing::Kind::Response,
+                }
+            }
+
+            async fn on_request(&self, req: &mut rocket::Request<'_>, _: &mut rocket::Data<'_>) {
+                req.local_cache(|| RequestStart(std::time::Instant::now()));
+
+                log::info!(target: "py_apify::request", "[{}] {} {}", RequestId::of(req), req.method(), req.uri());
+            }
+
+            async fn on_response<'r>(&self, req: &'r rocket::Request<'_>, res: &mut rocket::Response<'r>) {
+                let request_id = RequestId::of(req);
+                let duration = req.local_cache(|| RequestStart(std::time::Instant::now())).0.elapsed();
+
+                res.set_raw_header(py_apify_core::problem::REQUEST_ID_HEADER, request_id.0.clone());
+
+                log::info!(
+                    target: "py_apify::request",
+                    "[{}] {} {} (route {}) -> {} in {:.3}ms",
+                    request_id,
+                    req.method(),
+                    req.uri(),
+                    req.route().and_then(|route| route.name.as_deref()).unwrap_or("none"),
+                    res.status(),
+                    duration.as_secs_f64() * 1000.0
+                );
+            }
+        }
+
+        impl std::fmt::Display for RequestId {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+    }
+}
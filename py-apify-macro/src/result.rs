@@ -0,0 +1,13 @@
+use crate::TokenStream2;
+use quote::quote;
+
+pub fn gen_result() -> TokenStream2 {
+    quote! {
+        /// Serializes the result of `call`, see `py_result_to_json` in
+        /// py-apify-core.
+        fn py_result_to_json(file_name: &str, result: &pyo3::PyAny) -> Result<String, PyApifyError> {
+            py_apify_core::result::py_result_to_json(result)
+                .map_err(|e| PyApifyError::InvalidReturnValue(format!("{}: {}", file_name, e)))
+        }
+    }
+}
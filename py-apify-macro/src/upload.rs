@@ -0,0 +1,102 @@
+use crate::TokenStream2;
+use quote::quote;
+
+pub fn gen_upload() -> TokenStream2 {
+    quote! {
+        /// File of a multipart/form-data request, for a `call` parameter
+        /// annotated with `py_apify.UploadFile`. It is read in memory, up to
+        /// `max_upload_size` bytes.
+        #[derive(rocket::serde::Serialize, rocket::serde::Deserialize)]
+        #[serde(crate = "rocket::serde")]
+        pub struct UploadFile {
+            /// Name sent by the client, without its directories
+            pub filename: Option<String>,
+            pub content_type: Option<String>,
+            pub data: Vec<u8>,
+        }
+
+        #[rocket::async_trait]
+        impl<'v> rocket::form::FromFormField<'v> for UploadFile {
+            fn from_value(field: rocket::form::ValueField<'v>) -> rocket::form::Result<'v, Self> {
+                Ok(UploadFile {
+                    filename: None,
+                    content_type: None,
+                    data: field.value.as_bytes().to_vec(),
+                })
+            }
+
+            async fn from_data(field: rocket::form::DataField<'v, '_>) -> rocket::form::Result<'v, Self> {
+                let limit = field
+                    .request
+                    .rocket()
+                    .state::<PyApifyConfig>()
+                    .map(|config| config.max_upload_size)
+                    .unwrap_or_else(|| PyApifyConfig::default().max_upload_size);
+
+                let data = field
+                    .data
+                    .open(limit)
+                    .into_bytes()
+                    .await
+                    .map_err(|e| rocket::form::Error::from(rocket::form::error::ErrorKind::Io(e)))?;
+
+                if !data.is_complete() {
+                    return Err(rocket::form::Error::from(rocket::form::error::ErrorKind::InvalidLength {
+                        min: None,
+                        max: Some(limit.as_u64()),
+                    })
+                    .into());
+                }
+
+                let filename = field.file_name.map(|file_name| {
+                    let raw = file_name.dangerous_unsafe_unsanitized_raw().as_str();
+                    raw.rsplit(|c| c == '/' || c == '\\').next().unwrap_or(raw).to_string()
+                });
+
+                Ok(UploadFile {
+                    filename,
+                    content_type: Some(field.content_type.to_string()),
+                    data: data.into_inner(),
+                })
+            }
+        }
+
+        impl pyo3::IntoPy<pyo3::PyObject> for UploadFile {
+            fn into_py(self, py: pyo3::Python) -> pyo3::PyObject {
+                let data = pyo3::types::PyBytes::new(py, &self.data);
+
+                import_py_apify_module(py)
+                    .and_then(|module| module.getattr("UploadFile"))
+                    .and_then(|upload_file| upload_file.call1((self.filename, self.content_type, data)))
+                    .map(|upload_file| upload_file.into())
+                    .unwrap_or_else(|e| {
+                        log::error!("Failed to build a `py_apify.UploadFile`, passing its content: {}", e);
+                        data.into()
+                    })
+            }
+        }
+
+        /// Content of a file of a multipart/form-data request, for a `call`
+        /// parameter annotated with `bytes`.
+        #[derive(rocket::serde::Serialize, rocket::serde::Deserialize)]
+        #[serde(crate = "rocket::serde")]
+        pub struct UploadBytes(pub Vec<u8>);
+
+        #[rocket::async_trait]
+        impl<'v> rocket::form::FromFormField<'v> for UploadBytes {
+            fn from_value(field: rocket::form::ValueField<'v>) -> rocket::form::Result<'v, Self> {
+                UploadFile::from_value(field).map(|file| UploadBytes(file.data))
+            }
+
+            async fn from_data(field: rocket::form::DataField<'v, '_>) -> rocket::form::Result<'v, Self> {
+                UploadFile::from_data(field).await.map(|file| UploadBytes(file.data))
+            }
+        }
+
+        impl pyo3::IntoPy<pyo3::PyObject> for UploadBytes {
+            fn into_py(self, py: pyo3::Python) -> pyo3::PyObject {
+                pyo3::types::PyBytes::new(py, &self.0).into()
+            }
+        }
+    }
+}
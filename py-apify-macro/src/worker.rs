@@ -0,0 +1,317 @@
+use crate::form::FormIdent;
+use crate::hook::HookFunctionIdent;
+use crate::python_file::PythonFile;
+use proc_macro2::{Ident, Literal, TokenStream as TokenStream2};
+use quote::quote;
+
+pub fn gen_worker() -> TokenStream2 {
+    quote! {
+        /// Environment variable telling a process to run as a Python worker
+        /// for the given file stem instead of starting Rocket.
+        const PY_APIFY_WORKER_ENV: &str = "PY_APIFY_WORKER";
+
+        /// A line sent to a worker process.
+        #[derive(rocket::serde::Serialize, rocket::serde::Deserialize)]
+        #[serde(crate = "rocket::serde")]
+        struct WorkerRequest<I> {
+            request_id: String,
+            input: I,
+        }
+
+        struct WorkerProcess {
+            child: std::process::Child,
+            stdin: std::process::ChildStdin,
+            stdout: std::io::BufReader<std::process::ChildStdout>,
+        }
+
+        impl WorkerProcess {
+            fn spawn(file_stem: &str) -> std::io::Result<Self> {
+                let mut child = std::process::Command::new(std::env::current_exe()?)
+                    .args(std::env::args_os().skip(1))
+                    .env(PY_APIFY_WORKER_ENV, file_stem)
+                    .stdin(std::process::Stdio::piped())
+                    .stdout(std::process::Stdio::piped())
+                    .spawn()?;
+
+                let missing_pipe = || std::io::Error::new(std::io::ErrorKind::Other, "missing worker pipe");
+                let stdin = child.stdin.take().ok_or_else(missing_pipe)?;
+                let stdout = child.stdout.take().ok_or_else(missing_pipe)?;
+
+                Ok(WorkerProcess {
+                    child,
+                    stdin,
+                    stdout: std::io::BufReader::new(stdout),
+                })
+            }
+
+            fn is_alive(&mut self) -> bool {
+                matches!(self.child.try_wait(), Ok(None))
+            }
+
+            fn exchange(&mut self, input: &str) -> std::io::Result<Result<String, PyApifyError>> {
+                use std::io::{BufRead, Write};
+
+                writeln!(self.stdin, "{}", input)?;
+                self.stdin.flush()?;
+
+                let mut line = String::new();
+
+                if self.stdout.read_line(&mut line)? == 0 {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::UnexpectedEof,
+                        "the worker process exited",
+                    ));
+                }
+
+                rocket::serde::json::from_str(&line)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))
+            }
+
+            fn kill(mut self) {
+                let _ = self.child.kill();
+                let _ = self.child.wait();
+            }
+        }
+
+        /// Worker processes running the hook of one Python file, each one with
+        /// its own interpreter. Inputs are sent as JSON lines on the worker
+        /// stdin and results are read back from its stdout.
+        pub struct ProcessPool {
+            file_stem: &'static str,
+            workers: Vec<std::sync::Mutex<Option<WorkerProcess>>>,
+            next: std::sync::atomic::AtomicUsize,
+        }
+
+        impl ProcessPool {
+            pub fn new(file_stem: &'static str, size: usize) -> Self {
+                ProcessPool {
+                    file_stem,
+                    workers: (0..size.max(1))
+                        .map(|_| std::sync::Mutex::new(Self::spawn_worker(file_stem)))
+                        .collect(),
+                    next: std::sync::atomic::AtomicUsize::new(0),
+                }
+            }
+
+            fn spawn_worker(file_stem: &str) -> Option<WorkerProcess> {
+                WorkerProcess::spawn(file_stem)
+                    .map_err(|e| log::error!("Failed to start a worker process for `{}`: {}", file_stem, e))
+                    .ok()
+            }
+
+            /// Locks an idle worker, or waits for one in round robin order.
+            fn lock_worker(&self) -> std::sync::MutexGuard<'_, Option<WorkerProcess>> {
+                for worker in &self.workers {
+                    if let Ok(worker) = worker.try_lock() {
+                        return worker;
+                    }
+                }
+
+                let index = self.next.fetch_add(1, std::sync::atomic::Ordering::Relaxed) % self.workers.len();
+                self.workers[index].lock().unwrap_or_else(|e| e.into_inner())
+            }
+
+            /// Number of running workers, busy workers are counted as running.
+            pub fn alive_workers(&self) -> usize {
+                self.workers
+                    .iter()
+                    .filter(|worker| match worker.try_lock() {
+                        Ok(mut worker) => worker.as_mut().map_or(false, WorkerProcess::is_alive),
+                        Err(std::sync::TryLockError::WouldBlock) => true,
+                        Err(std::sync::TryLockError::Poisoned(e)) => {
+                            e.into_inner().as_mut().map_or(false, WorkerProcess::is_alive)
+                        }
+                    })
+                    .count()
+            }
+
+            /// Sends `input` to a worker and blocks until it answers. Workers
+            /// that exited or crashed are restarted.
+            pub fn call(&self, input: &str) -> Result<String, PyApifyError> {
+                let mut worker = self.lock_worker();
+
+                if !worker.as_mut().map_or(false, WorkerProcess::is_alive) {
+                    log::warn!("Restarting a worker process for `{}`", self.file_stem);
+                    if let Some(dead_worker) = worker.take() {
+                        dead_worker.kill();
+                    }
+                    *worker = Self::spawn_worker(self.file_stem);
+                }
+
+                let result = match worker.as_mut() {
+                    Some(process) => process.exchange(input),
+                    None => {
+                        return Err(PyApifyError::WorkerFailure(format!(
+                            "no worker process is running for `{}`",
+                            self.file_stem
+                        )))
+                    }
+                };
+
+                result.unwrap_or_else(|e| {
+                    log::error!("Worker process for `{}` failed, restarting it: {}", self.file_stem, e);
+                    if let Some(crashed_worker) = worker.take() {
+                        crashed_worker.kill();
+                    }
+                    *worker = Self::spawn_worker(self.file_stem);
+
+                    Err(PyApifyError::WorkerFailure(e.to_string()))
+                })
+            }
+        }
+
+        /// Main loop of a worker process: loads one Python file with
+        /// `load_py_module`, then answers each JSON line read on stdin with the
+        /// result of `call_hook`, as a JSON line on the original stdout.
+        fn run_worker(
+            py: pyo3::Python,
+            file_stem: &str,
+            load_py_module: fn(pyo3::Python, &str) -> pyo3::PyResult<()>,
+            call_hook: fn(pyo3::Python, &str, &str) -> Result<String, PyApifyError>,
+        ) -> ! {
+            use std::io::BufRead;
+
+            // stdout is the channel to the parent process, it's moved away
+            // before the file is imported so that prints can't reach it
+            let channel: pyo3::PyObject = match import_py_apify_module(py).and_then(|module| module.call_method0("_worker_channel")) {
+                Ok(channel) => channel.into(),
+                Err(e) => {
+                    log::error!("Worker process for `{}` failed to open its channel: {}", file_stem, e);
+                    std::process::exit(1);
+                }
+            };
+
+            if let Err(e) = load_py_module(py, file_stem) {
+                log::error!("Worker process failed to load `{}`: {}", file_stem, e);
+                std::process::exit(1);
+            }
+
+            let stdin = std::io::stdin();
+            let mut line = String::new();
+
+            loop {
+                line.clear();
+
+                match py.allow_threads(|| stdin.lock().read_line(&mut line)) {
+                    Ok(0) => std::process::exit(0),
+                    Ok(_) => {}
+                    Err(e) => {
+                        log::error!("Worker process for `{}` failed to read its input: {}", file_stem, e);
+                        std::process::exit(1);
+                    }
+                }
+
+                let result = match rocket::serde::json::from_str::<WorkerRequest<rocket::serde::json::Value>>(&line) {
+                    Ok(request) => {
+                        set_py_request_id(py, Some(&request.request_id));
+                        let result = call_hook(py, file_stem, &request.input.to_string());
+                        set_py_request_id(py, None);
+
+                        result
+                    }
+                    Err(e) => Err(PyApifyError::WorkerFailure(format!("invalid worker request: {}", e))),
+                };
+                let response = rocket::serde::json::to_string(&result).unwrap_or_else(|e| {
+                    log::error!("Worker process for `{}` failed to serialize its response: {}", file_stem, e);
+                    // `Err(PyApifyError::WorkerFailure(..))`, which can't fail to serialize
+                    r#"{"Err":{"WorkerFailure":"failed to serialize the worker response"}}"#.to_string()
+                });
+
+                let written = channel
+                    .call_method1(py, "write", (format!("{}\n", response),))
+                    .and_then(|_| channel.call_method0(py, "flush"));
+
+                if written.is_err() {
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+}
+
+/// Generates `call_hook`, which a worker process uses to run the hook of a
+/// Python file on an input received as JSON.
+pub struct WorkerDispatch {
+    file_stems: Vec<Literal>,
+    hook_function_idents: Vec<HookFunctionIdent>,
+    form_idents: Vec<FormIdent>,
+}
+
+impl From<&Vec<PythonFile>> for WorkerDispatch {
+    fn from(python_files: &Vec<PythonFile>) -> WorkerDispatch {
+        WorkerDispatch {
+            file_stems: python_files
+                .iter()
+                .map(|file| Literal::string(&file.file_stem))
+                .collect(),
+            hook_function_idents: python_files.iter().map(|file| file.into()).collect(),
+            form_idents: python_files.iter().map(FormIdent::from).collect(),
+        }
+    }
+}
+
+impl From<WorkerDispatch> for TokenStream2 {
+    fn from(worker_dispatch: WorkerDispatch) -> Self {
+        let file_stems = worker_dispatch.file_stems;
+
+        let hook_function_idents = worker_dispatch
+            .hook_function_idents
+            .into_iter()
+            .map(|e| e.into())
+            .collect::<Vec<Ident>>();
+
+        let form_idents = worker_dispatch
+            .form_idents
+            .into_iter()
+            .map(|e| e.into())
+            .collect::<Vec<Ident>>();
+
+        quote! {
+            fn call_hook(py: pyo3::Python, file_stem: &str, input: &str) -> Result<String, PyApifyError> {
+                match file_stem {
+                    #(
+                        #file_stems => #hook_function_idents(
+                            py,
+                            rocket::serde::json::from_str::<#form_idents>(input).map_err(|_| PyApifyError::InvalidArguments)?,
+                        ),
+                    )*
+                    _ => Err(PyApifyError::HookFunctionNotFound(file_stem.to_string())),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_worker_dispatch() {
+        let py_file = PythonFile {
+            file_name: "test.py".into(),
+            file_stem: "test".into(),
+            ident: "test".into(),
+            main_func_args: vec![],
+            doc: None,
+            path: PathBuf::from("/test.py"),
+        };
+
+        let token_stream: TokenStream2 = WorkerDispatch::from(&vec![py_file]).into();
+
+        let target_ts = quote! {
+            fn call_hook(py: pyo3::Python, file_stem: &str, input: &str) -> Result<String, PyApifyError> {
+                match file_stem {
+                    "test" => hook_test(
+                        py,
+                        rocket::serde::json::from_str::<Form_test>(input).map_err(|_| PyApifyError::InvalidArguments)?,
+                    ),
+                    _ => Err(PyApifyError::HookFunctionNotFound(file_stem.to_string())),
+                }
+            }
+        };
+
+        assert_eq!(token_stream.to_string(), target_ts.to_string());
+    }
+}
@@ -0,0 +1,63 @@
+#[macro_use]
+extern crate rocket;
+
+use py_apify_macro::apify;
+use rocket::http::Status;
+use rocket::local::blocking::Client;
+use rocket::serde::json::{json, Value};
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    apify! { "tests/py/bounded.py" }
+}
+
+#[test]
+fn test_constraints_are_checked() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    assert_eq!(
+        client
+            .get("/bounded?text=Paris&top_k=50")
+            .dispatch()
+            .status(),
+        Status::Ok
+    );
+
+    for response in [
+        client.get("/bounded?text=Paris&top_k=51").dispatch(),
+        client
+            .post("/bounded")
+            .json(&json!({ "text": "Paris", "top_k": 0 }))
+            .dispatch(),
+    ] {
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+
+        let problem: Value = response.into_json().unwrap();
+        assert_eq!(problem["fields"][0]["name"], "top_k");
+        assert_eq!(
+            problem["fields"][0]["reason"],
+            "value must be between 1 and 50"
+        );
+    }
+
+    let response = client
+        .post("/bounded")
+        .json(&json!({ "text": "Saint-Germain-en-Laye" }))
+        .dispatch();
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+}
+
+#[test]
+fn test_constraints_schema() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    let document: Value = client.get("/openapi.json").dispatch().into_json().unwrap();
+    let properties = &document["paths"]["/bounded"]["post"]["requestBody"]["content"]
+        ["application/json"]["schema"]["properties"];
+
+    assert_eq!(
+        properties["text"],
+        json!({ "type": "string", "maxLength": 10 })
+    );
+    assert_eq!(properties["top_k"]["minimum"], 1.0);
+    assert_eq!(properties["top_k"]["maximum"], 50.0);
+}
@@ -0,0 +1,94 @@
+#[macro_use]
+extern crate rocket;
+
+use py_apify_macro::apify;
+use rocket::http::Status;
+use rocket::local::blocking::Client;
+use rocket::serde::json::{json, Value};
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    apify! { "tests/py/modes.py" }
+}
+
+#[test]
+fn test_enum_values_reach_python() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    let response = client
+        .get("/modes?aggregation=max&precision=accurate")
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        response.into_json::<Value>().unwrap(),
+        json!({ "aggregation": "max", "precision": "ACCURATE" })
+    );
+
+    let response = client
+        .post("/modes")
+        .json(&json!({ "aggregation": "first" }))
+        .dispatch();
+    assert_eq!(
+        response.into_json::<Value>().unwrap(),
+        json!({ "aggregation": "first", "precision": "FAST" })
+    );
+}
+
+#[test]
+fn test_invalid_enum_values() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    for response in [
+        client.get("/modes?aggregation=mean").dispatch(),
+        client
+            .post("/modes")
+            .json(&json!({ "aggregation": "mean" }))
+            .dispatch(),
+    ] {
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+
+        let problem: Value = response.into_json().unwrap();
+        let field = &problem["fields"][0];
+
+        assert_eq!(field["name"], "aggregation");
+        assert_eq!(
+            field["expected"],
+            "Literal[\"simple\", \"first\", \"max\"] | None"
+        );
+        assert!(field["reason"].as_str().unwrap().contains("max"));
+    }
+}
+
+#[test]
+fn test_invalid_enum_members() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    for response in [
+        client.get("/modes?precision=slow").dispatch(),
+        client
+            .post("/modes")
+            .json(&json!({ "precision": "slow" }))
+            .dispatch(),
+    ] {
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+
+        let problem: Value = response.into_json().unwrap();
+        let fields = problem["fields"].as_array().unwrap();
+
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0]["name"], "precision");
+        assert!(fields[0]["reason"].as_str().unwrap().contains("accurate"));
+    }
+}
+
+#[test]
+fn test_enum_schema() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    let document: Value = client.get("/openapi.json").dispatch().into_json().unwrap();
+    let parameters = &document["paths"]["/modes"]["get"]["parameters"];
+
+    assert_eq!(
+        parameters[1]["schema"],
+        json!({ "type": "string", "enum": ["fast", "accurate"], "title": "Precision", })
+    );
+}
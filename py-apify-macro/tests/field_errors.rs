@@ -0,0 +1,101 @@
+#[macro_use]
+extern crate rocket;
+
+use py_apify_macro::apify;
+use rocket::http::Status;
+use rocket::local::blocking::{Client, LocalResponse};
+use rocket::serde::json::{json, Value};
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    apify! { "tests/py/typed.py" }
+}
+
+fn field_errors(response: LocalResponse) -> Vec<Value> {
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+
+    let problem: Value = response.into_json().expect("invalid problem document");
+    assert_eq!(problem["type"], "urn:py-apify:error:invalid-fields");
+
+    problem["fields"]
+        .as_array()
+        .expect("missing fields")
+        .clone()
+}
+
+fn field<'a>(fields: &'a [Value], name: &str) -> &'a Value {
+    fields
+        .iter()
+        .find(|field| field["name"] == name)
+        .unwrap_or_else(|| panic!("`{}` not found in {:?}", name, fields))
+}
+
+#[test]
+fn test_query_field_errors() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    let fields = field_errors(client.get("/typed?top_k=five&foo=bar").dispatch());
+
+    assert_eq!(fields.len(), 3);
+    assert_eq!(field(&fields, "text")["expected"], "str");
+    assert_eq!(field(&fields, "top_k")["expected"], "int | None");
+    assert_eq!(field(&fields, "foo")["expected"], Value::Null);
+}
+
+#[test]
+fn test_json_field_errors() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    let fields = field_errors(
+        client
+            .post("/typed")
+            .json(&json!({ "top_k": "five", "threshold": 0.5, "foo": "bar" }))
+            .dispatch(),
+    );
+
+    assert_eq!(fields.len(), 3);
+    assert_eq!(
+        field(&fields, "text"),
+        &json!({ "name": "text", "expected": "str", "reason": "missing" })
+    );
+    assert_eq!(
+        field(&fields, "foo"),
+        &json!({ "name": "foo", "expected": null, "reason": "unexpected" })
+    );
+    assert_eq!(field(&fields, "top_k")["expected"], "int | None");
+    assert!(field(&fields, "top_k")["reason"]
+        .as_str()
+        .unwrap()
+        .contains("invalid type"));
+}
+
+#[test]
+fn test_json_body_must_be_an_object() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    let fields = field_errors(client.post("/typed").json(&json!(["text"])).dispatch());
+
+    assert_eq!(fields[0]["name"], "");
+    assert_eq!(fields[0]["expected"], "object");
+}
+
+#[test]
+fn test_valid_fields() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    let response = client.get("/typed?text=Paris&top_k=-3").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        response.into_json::<Value>(),
+        Some(json!({ "text": "Paris", "top_k": -3, "threshold": null }))
+    );
+
+    let response = client
+        .post("/typed")
+        .json(&json!({ "text": "Paris", "threshold": 0.5 }))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        response.into_json::<Value>(),
+        Some(json!({ "text": "Paris", "top_k": 5, "threshold": 0.5 }))
+    );
+}
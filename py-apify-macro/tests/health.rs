@@ -0,0 +1,88 @@
+#[macro_use]
+extern crate rocket;
+
+use py_apify_macro::apify;
+use rocket::http::Status;
+use rocket::local::blocking::Client;
+use rocket::serde::json::{json, Value};
+
+fn ready_rocket() -> rocket::Rocket<rocket::Build> {
+    apify! { "tests/py/ready.py" }
+}
+
+fn not_ready_rocket() -> rocket::Rocket<rocket::Build> {
+    apify! { "tests/py/ready.py", "tests/py/not_ready.py" }
+}
+
+fn broken_rocket() -> rocket::Rocket<rocket::Build> {
+    apify! { "tests/py/broken.py" }
+}
+
+#[test]
+fn test_healthz() {
+    let client = Client::tracked(not_ready_rocket()).expect("failed to build rocket");
+
+    let response = client.get("/healthz").dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        response.into_json::<Value>(),
+        Some(json!({ "status": "ok" }))
+    );
+}
+
+#[test]
+fn test_readyz() {
+    let client = Client::tracked(ready_rocket()).expect("failed to build rocket");
+
+    let response = client.get("/readyz").dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        response.into_json::<Value>(),
+        Some(json!({
+            "status": "ready",
+            "modules": { "ready": { "ready": true, "error": null } },
+        }))
+    );
+}
+
+#[test]
+fn test_readyz_reports_each_module() {
+    let client = Client::tracked(not_ready_rocket()).expect("failed to build rocket");
+
+    let response = client.get("/readyz").dispatch();
+
+    assert_eq!(response.status(), Status::ServiceUnavailable);
+    assert_eq!(
+        response.into_json::<Value>(),
+        Some(json!({
+            "status": "not ready",
+            "modules": {
+                "ready": { "ready": true, "error": null },
+                "not_ready": { "ready": false, "error": null },
+            },
+        }))
+    );
+}
+
+#[test]
+fn test_module_failing_to_import() {
+    let client = Client::tracked(broken_rocket()).expect("failed to build rocket");
+
+    let response = client.get("/readyz").dispatch();
+    assert_eq!(response.status(), Status::ServiceUnavailable);
+
+    let readyz: Value = response.into_json().unwrap();
+    assert!(readyz["modules"]["broken"]["error"]
+        .as_str()
+        .unwrap()
+        .contains("broken at import"));
+
+    let response = client.get("/broken?text=a").dispatch();
+    assert_eq!(response.status(), Status::ServiceUnavailable);
+
+    let problem: Value = response.into_json().unwrap();
+    assert_eq!(problem["type"], "urn:py-apify:error:module-not-loaded");
+    assert_eq!(problem["status"], 503);
+}
@@ -0,0 +1,59 @@
+#[macro_use]
+extern crate rocket;
+
+use py_apify_macro::apify;
+use rocket::local::blocking::Client;
+use rocket::serde::json::{json, Value};
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    apify! { "tests/py/documented.py" }
+}
+
+#[test]
+fn test_index_lists_documented_routes() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    let index: Value = client.get("/").dispatch().into_json().unwrap();
+
+    assert_eq!(
+        index,
+        json!({
+            "endpoints": [{
+                "path": "/documented",
+                "file": "documented.py",
+                "methods": ["GET", "POST"],
+                "description": "Splits a text into its first words.",
+                "parameters": [
+                    {
+                        "name": "text",
+                        "type": "str",
+                        "required": true,
+                        "default": null,
+                        "description": "The text to split.",
+                    },
+                    {
+                        "name": "top_k",
+                        "type": "int",
+                        "required": false,
+                        "default": 3,
+                        "description": "Number of words to keep.",
+                    },
+                ],
+            }]
+        })
+    );
+}
+
+#[test]
+fn test_openapi_descriptions() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    let document: Value = client.get("/openapi.json").dispatch().into_json().unwrap();
+    let get = &document["paths"]["/documented"]["get"];
+
+    assert_eq!(get["description"], "Splits a text into its first words.");
+    assert_eq!(
+        get["parameters"][1]["schema"]["description"],
+        "Number of words to keep."
+    );
+}
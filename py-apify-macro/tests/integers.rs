@@ -0,0 +1,64 @@
+#[macro_use]
+extern crate rocket;
+
+use py_apify_macro::apify;
+use rocket::http::Status;
+use rocket::local::blocking::Client;
+use rocket::serde::json::{json, Value};
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    apify! { "tests/py/offsets.py" }
+}
+
+const OVERFLOWING: &str = "99999999999999999999";
+
+#[test]
+fn test_negative_ints_reach_python() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    for n in [-42i64, -1, i64::MIN] {
+        let response = client
+            .get(format!("/offsets?n={}&offset={}", n, n))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response.into_json::<Value>().unwrap(),
+            json!({ "n": n, "offset": n })
+        );
+    }
+}
+
+#[test]
+fn test_overflowing_ints_are_rejected() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    for (uri, name) in [
+        (format!("/offsets?n={}", OVERFLOWING), "n"),
+        (format!("/offsets?n=1&offset={}", OVERFLOWING), "offset"),
+        (format!("/offsets?n=1&offset=-{}", OVERFLOWING), "offset"),
+    ] {
+        let response = client.get(uri).dispatch();
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+
+        let problem: Value = response.into_json().unwrap();
+        let fields = problem["fields"].as_array().unwrap();
+
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0]["name"], name);
+        assert!(fields[0]["reason"]
+            .as_str()
+            .unwrap()
+            .ends_with("to fit in target type"));
+    }
+
+    let response = client
+        .post("/offsets")
+        .header(rocket::http::ContentType::JSON)
+        .body(format!(r#"{{"n": 1, "offset": {}}}"#, OVERFLOWING))
+        .dispatch();
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+
+    let problem: Value = response.into_json().unwrap();
+    assert_eq!(problem["fields"][0]["name"], "offset");
+}
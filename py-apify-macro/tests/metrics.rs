@@ -0,0 +1,46 @@
+#[macro_use]
+extern crate rocket;
+
+use py_apify_macro::apify;
+use rocket::http::Status;
+use rocket::local::blocking::Client;
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    apify! { "tests/py/typed.py" }
+}
+
+#[test]
+fn test_metrics() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    assert_eq!(client.get("/typed?text=a").dispatch().status(), Status::Ok);
+    assert_eq!(client.get("/typed?text=b").dispatch().status(), Status::Ok);
+    assert_eq!(
+        client.get("/typed?top_k=c").dispatch().status(),
+        Status::UnprocessableEntity
+    );
+
+    let response = client.get("/metrics").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    let metrics = response.into_string().expect("missing metrics");
+    let lines: Vec<&str> = metrics.lines().collect();
+
+    for expected in &[
+        "# TYPE py_apify_requests_total counter",
+        r#"py_apify_requests_total{file_stem="typed"} 3"#,
+        r#"py_apify_errors_total{file_stem="typed",error="invalid-fields"} 1"#,
+        "# TYPE py_apify_request_duration_seconds histogram",
+        r#"py_apify_request_duration_seconds_bucket{file_stem="typed",le="+Inf"} 3"#,
+        r#"py_apify_request_duration_seconds_count{file_stem="typed"} 3"#,
+        r#"py_apify_gil_wait_seconds_count{file_stem="typed"} 2"#,
+        r#"py_apify_python_duration_seconds_count{file_stem="typed"} 2"#,
+    ] {
+        assert!(
+            lines.contains(expected),
+            "{} not found in\n{}",
+            expected,
+            metrics
+        );
+    }
+}
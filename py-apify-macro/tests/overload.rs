@@ -0,0 +1,41 @@
+//! Single test: the pool is configured through `ROCKET_PY_APIFY`, which is
+//! shared by every test of the executable.
+
+#[macro_use]
+extern crate rocket;
+
+use py_apify_macro::apify;
+use rocket::http::Status;
+use rocket::local::asynchronous::Client;
+use rocket::serde::json::Value;
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    apify! { "tests/py/slow.py" }
+}
+
+/// With a single worker and no queue, a request arriving while a hook runs is
+/// answered with a 503. Both requests are polled by the same task, so the
+/// second one is only handled while the first waits if the hook runs off the
+/// async executor.
+#[rocket::async_test]
+async fn test_overloaded() {
+    std::env::set_var("ROCKET_PY_APIFY", "{blocking_workers=1,queue_depth=0}");
+
+    let client = Client::tracked(rocket()).await.expect("failed to build rocket");
+
+    let (first, second) = rocket::tokio::join!(
+        client.get("/slow?seconds=0.5").dispatch(),
+        client.get("/slow?seconds=0").dispatch(),
+    );
+
+    assert_eq!(first.status(), Status::Ok);
+    assert_eq!(second.status(), Status::ServiceUnavailable);
+
+    let problem: Value = second.into_json().await.unwrap();
+    assert_eq!(problem["type"], "urn:py-apify:error:overloaded");
+    assert_eq!(problem["status"], 503);
+
+    // the rejected request doesn't hold on to the slot
+    let response = client.get("/slow?seconds=0").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+}
@@ -0,0 +1,105 @@
+#[macro_use]
+extern crate rocket;
+
+use py_apify_macro::apify;
+use rocket::http::{ContentType, Status};
+use rocket::local::blocking::Client;
+use rocket::serde::json::{json, Value};
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    apify! { "tests/py/hostile.py" }
+}
+
+const HOSTILE_MESSAGES: &[&str] = &[
+    r#"unbalanced " quote"#,
+    r#"{"error": "injected"}"#,
+    "back\\slash \\\" escaped quote",
+    "Traceback (most recent call last):\n  File \"hostile.py\", line 6\n\tRuntimeError",
+    "control \u{0} \u{1b}[31m characters \r\n",
+    "unicode: 日本語 \u{2028} 😀",
+    "</script><script>alert(1)</script>",
+];
+
+fn problem(client: &Client, message: &str, status: i64) -> (Status, Option<ContentType>, Value) {
+    let response = client
+        .post("/hostile")
+        .json(&json!({ "message": message, "status": status }))
+        .dispatch();
+
+    let response_status = response.status();
+    let content_type = response.content_type();
+    let body = response.into_string().expect("missing body");
+    let problem: Value = rocket::serde::json::from_str(&body)
+        .unwrap_or_else(|e| panic!("invalid JSON ({}): {}", e, body));
+
+    (response_status, content_type, problem)
+}
+
+#[test]
+fn test_hostile_messages_are_escaped() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    for message in HOSTILE_MESSAGES {
+        let (status, content_type, problem) = problem(&client, message, 500);
+
+        assert_eq!(status, Status::InternalServerError);
+        assert_eq!(
+            content_type,
+            Some(ContentType::new("application", "problem+json"))
+        );
+        assert_eq!(problem["type"], "urn:py-apify:error:hook-function-failure");
+        assert_eq!(problem["status"], 500);
+        assert!(problem["title"].is_string());
+        assert!(
+            problem["detail"].as_str().unwrap().contains(message),
+            "{:?} not found in {}",
+            message,
+            problem["detail"]
+        );
+        assert!(problem["instance"]
+            .as_str()
+            .unwrap()
+            .starts_with("urn:py-apify:request:"));
+    }
+}
+
+#[test]
+fn test_http_error_keeps_detail_and_status() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    for message in HOSTILE_MESSAGES {
+        let (status, _, problem) = problem(&client, message, 404);
+
+        assert_eq!(status, Status::NotFound);
+        assert_eq!(problem["type"], "urn:py-apify:error:python-exception");
+        assert_eq!(problem["status"], 404);
+        assert_eq!(problem["detail"], *message);
+        assert_eq!(problem["exception"], "HTTPError");
+    }
+}
+
+#[test]
+fn test_request_ids_are_unique() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    let (_, _, first) = problem(&client, "first", 500);
+    let (_, _, second) = problem(&client, "second", 500);
+
+    assert_ne!(first["instance"], second["instance"]);
+}
+
+#[test]
+fn test_success_is_plain_json() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    let response = client
+        .post("/hostile")
+        .json(&json!({ "message": "\"ok\"\n", "status": 0 }))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        response.into_json::<Value>(),
+        Some(json!({ "message": "\"ok\"\n" }))
+    );
+}
@@ -0,0 +1,7 @@
+from typing import Annotated
+
+from py_apify import MaxLen, Range
+
+
+def call(text: Annotated[str, MaxLen(10)], top_k: Annotated[int, Range(1, 50)] = 5):
+    return {"text": text, "top_k": top_k}
@@ -0,0 +1,5 @@
+raise RuntimeError("broken at import")
+
+
+def call(text: str):
+    return {"text": text}
@@ -0,0 +1,9 @@
+import logging
+
+logger = logging.getLogger(__name__)
+
+
+def call(text: str):
+    print("printing", text)
+    logger.warning("logging %s", text)
+    return {"text": text}
@@ -0,0 +1,18 @@
+import os
+import sys
+
+# printed while the file is imported, none of it may reach the parent process
+print("crashy is loading")
+sys.__stdout__.write("written to sys.__stdout__\n")
+sys.__stdout__.flush()
+os.write(1, b"written to file descriptor 1\n")
+
+
+def call(crash: bool = False):
+    if crash:
+        os._exit(3)
+
+    print("printed by call")
+    os.write(1, b"written to file descriptor 1 by call\n")
+
+    return {"pid": os.getpid()}
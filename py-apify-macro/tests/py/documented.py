@@ -0,0 +1,8 @@
+def call(text: str, top_k: int = 3):
+    """Splits a text into its first words.
+
+    Args:
+        text: The text to split.
+        top_k: Number of words to keep.
+    """
+    return text.split()[:top_k]
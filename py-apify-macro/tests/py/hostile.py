@@ -0,0 +1,11 @@
+from py_apify import HTTPError
+
+
+def call(message: str, status: int = 500):
+    if status == 500:
+        raise RuntimeError(message)
+
+    if status > 0:
+        raise HTTPError(status, message)
+
+    return {"message": message}
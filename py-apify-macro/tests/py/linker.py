@@ -0,0 +1,24 @@
+from dataclasses import dataclass
+from typing import TypedDict
+
+
+class Span(TypedDict):
+    start: int
+    end: int
+
+
+@dataclass
+class Document:
+    text: str
+    spans: list[Span]
+    lang: str = "en"
+
+
+def call(doc: Document):
+    if not isinstance(doc, Document):
+        raise TypeError(f"expected a Document, got {type(doc).__name__}")
+
+    return {
+        "lang": doc.lang,
+        "mentions": [doc.text[span["start"]:span["end"]] for span in doc.spans],
+    }
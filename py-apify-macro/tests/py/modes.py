@@ -0,0 +1,11 @@
+from enum import Enum
+from typing import Literal
+
+
+class Precision(str, Enum):
+    FAST = "fast"
+    ACCURATE = "accurate"
+
+
+def call(aggregation: Literal["simple", "first", "max"] = "simple", precision: Precision = Precision.FAST):
+    return {"aggregation": aggregation, "precision": precision.name}
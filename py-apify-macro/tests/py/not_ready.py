@@ -0,0 +1,6 @@
+def ready():
+    return False
+
+
+def call(text: str):
+    return {"text": text}
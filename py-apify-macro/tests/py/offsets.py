@@ -0,0 +1,5 @@
+from typing import Optional
+
+
+def call(n: int, offset: Optional[int] = None):
+    return {"n": n, "offset": offset}
@@ -0,0 +1,9 @@
+model = {"loaded": True}
+
+
+def ready():
+    return model["loaded"]
+
+
+def call(text: str):
+    return {"text": text}
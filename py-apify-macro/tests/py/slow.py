@@ -0,0 +1,7 @@
+import time
+
+
+def call(seconds: float):
+    time.sleep(seconds)
+
+    return {"slept": seconds}
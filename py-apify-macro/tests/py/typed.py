@@ -0,0 +1,5 @@
+from typing import Optional
+
+
+def call(text: str, top_k: int = 5, threshold: Optional[float] = None):
+    return {"text": text, "top_k": top_k, "threshold": threshold}
@@ -0,0 +1,11 @@
+import py_apify
+
+
+def call(scan: py_apify.UploadFile, audio: bytes, lang: str = "fr"):
+    return {
+        "filename": scan.filename,
+        "content_type": scan.content_type,
+        "scan": scan.read().decode(),
+        "audio": len(audio),
+        "lang": lang,
+    }
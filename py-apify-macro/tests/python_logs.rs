@@ -0,0 +1,59 @@
+#[macro_use]
+extern crate rocket;
+
+use py_apify_macro::apify;
+use rocket::http::{Header, Status};
+use rocket::local::blocking::Client;
+use std::sync::Mutex;
+
+struct TestLogger(Mutex<Vec<(log::Level, String, String)>>);
+
+impl log::Log for TestLogger {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        self.0.lock().unwrap().push((
+            record.level(),
+            record.target().to_string(),
+            record.args().to_string(),
+        ));
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: TestLogger = TestLogger(Mutex::new(vec![]));
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    apify! { "tests/py/chatty.py" }
+}
+
+#[test]
+fn test_python_logs_reach_the_log_crate() {
+    log::set_logger(&LOGGER).expect("failed to set the logger");
+    log::set_max_level(log::LevelFilter::Info);
+
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+    let response = client
+        .get("/chatty?text=Paris")
+        .header(Header::new("X-Request-Id", "paris-1"))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.headers().get_one("X-Request-Id"), Some("paris-1"));
+
+    let records = LOGGER.0.lock().unwrap();
+
+    assert!(records.contains(&(
+        log::Level::Info,
+        "chatty".to_string(),
+        "[paris-1] printing Paris".to_string()
+    )));
+    assert!(records.contains(&(
+        log::Level::Warn,
+        "chatty".to_string(),
+        "[paris-1] logging Paris".to_string()
+    )));
+}
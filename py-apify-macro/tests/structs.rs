@@ -0,0 +1,76 @@
+#[macro_use]
+extern crate rocket;
+
+use py_apify_macro::apify;
+use rocket::http::Status;
+use rocket::local::blocking::Client;
+use rocket::serde::json::{json, Value};
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    apify! { "tests/py/linker.py" }
+}
+
+#[test]
+fn test_nested_body_reaches_python() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    let response = client
+        .post("/linker")
+        .json(&json!({
+            "doc": {
+                "text": "Paris and Lyon",
+                "spans": [{ "start": 0, "end": 5 }, { "start": 10, "end": 14 }],
+            }
+        }))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        response.into_json::<Value>().unwrap(),
+        json!({ "lang": "en", "mentions": ["Paris", "Lyon"] })
+    );
+
+    let response = client
+        .get("/linker?doc.text=Paris&doc.lang=fr&doc.spans[0].start=0&doc.spans[0].end=5")
+        .dispatch();
+    assert_eq!(
+        response.into_json::<Value>().unwrap(),
+        json!({ "lang": "fr", "mentions": ["Paris"] })
+    );
+}
+
+#[test]
+fn test_invalid_nested_body() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    let response = client
+        .post("/linker")
+        .json(&json!({ "doc": { "text": "Paris", "spans": [{ "start": 0 }] } }))
+        .dispatch();
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+
+    let problem: Value = response.into_json().unwrap();
+    let field = &problem["fields"][0];
+
+    assert_eq!(field["name"], "doc");
+    assert_eq!(field["expected"], "Document");
+    assert!(field["reason"].as_str().unwrap().contains("end"));
+}
+
+#[test]
+fn test_struct_schema() {
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    let document: Value = client.get("/openapi.json").dispatch().into_json().unwrap();
+    let body = &document["paths"]["/linker"]["post"]["requestBody"]["content"]["application/json"]
+        ["schema"];
+
+    assert_eq!(body["properties"]["doc"]["title"], "Document");
+    assert_eq!(
+        body["properties"]["doc"]["required"],
+        json!(["text", "spans"])
+    );
+    assert_eq!(
+        body["properties"]["doc"]["properties"]["spans"]["items"]["required"],
+        json!(["start", "end"])
+    );
+}
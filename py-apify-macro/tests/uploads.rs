@@ -0,0 +1,81 @@
+#[macro_use]
+extern crate rocket;
+
+use py_apify_macro::apify;
+use rocket::http::{ContentType, Status};
+use rocket::local::blocking::Client;
+use rocket::serde::json::{json, Value};
+
+const BOUNDARY: &str = "py-apify-boundary";
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    apify! { "tests/py/upload.py" }
+}
+
+fn multipart_body(audio: &[u8]) -> Vec<u8> {
+    let mut body = format!(
+        "--{b}\r\n\
+         Content-Disposition: form-data; name=\"scan\"; filename=\"../scans/page-1.txt\"\r\n\
+         Content-Type: text/plain\r\n\r\n\
+         bonjour\r\n\
+         --{b}\r\n\
+         Content-Disposition: form-data; name=\"audio\"; filename=\"audio.wav\"\r\n\
+         Content-Type: audio/wav\r\n\r\n",
+        b = BOUNDARY
+    )
+    .into_bytes();
+
+    body.extend_from_slice(audio);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", BOUNDARY).as_bytes());
+    body
+}
+
+fn multipart_content_type() -> ContentType {
+    ContentType::new("multipart", "form-data").with_params(("boundary", BOUNDARY))
+}
+
+#[test]
+fn test_uploads() {
+    // a single test, the environment is shared by the tests of this file
+    std::env::set_var("ROCKET_PY_APIFY", "{max_upload_size=64}");
+
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    let response = client
+        .post("/upload")
+        .header(multipart_content_type())
+        .body(multipart_body(&[0u8; 32]))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        response.into_json::<Value>().unwrap(),
+        json!({
+            "filename": "page-1.txt",
+            "content_type": "text/plain",
+            "scan": "bonjour",
+            "audio": 32,
+            "lang": "fr",
+        })
+    );
+
+    let response = client
+        .post("/upload")
+        .header(multipart_content_type())
+        .body(multipart_body(&[0u8; 128]))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+
+    let problem: Value = response.into_json().unwrap();
+    assert_eq!(problem["fields"][0]["name"], "audio");
+    assert_eq!(problem["fields"][0]["expected"], "bytes");
+
+    // the upload route only accepts POST, the catcher of `/upload` keeps the 404
+    let response = client.get("/upload").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+
+    let problem: Value = response.into_json().unwrap();
+    assert_eq!(problem["type"], "urn:py-apify:error:not-found");
+    assert_eq!(problem["detail"], "No route matches GET /upload");
+}
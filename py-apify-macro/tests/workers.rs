@@ -0,0 +1,56 @@
+//! Runs without the libtest harness: worker processes are spawned from the
+//! current executable, which must start serving the hook as soon as it builds
+//! the Rocket instance, without printing anything on its stdout first.
+
+#[macro_use]
+extern crate rocket;
+
+use py_apify_macro::apify;
+use rocket::http::Status;
+use rocket::local::blocking::Client;
+use rocket::serde::json::Value;
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    apify! { "tests/py/crashy.py" }
+}
+
+fn worker_pid(client: &Client) -> u64 {
+    let response = client.get("/crashy").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    let pid = response.into_json::<Value>().unwrap()["pid"]
+        .as_u64()
+        .unwrap();
+    assert_ne!(pid, u64::from(std::process::id()));
+
+    pid
+}
+
+/// Prints at import and in `call` don't corrupt the responses of the worker.
+fn test_prints_stay_out_of_the_channel(client: &Client) {
+    let pid = worker_pid(client);
+
+    assert_eq!(worker_pid(client), pid);
+}
+
+/// A worker that crashes fails its request and is restarted for the next one.
+fn test_crashed_worker_is_restarted(client: &Client) {
+    let pid = worker_pid(client);
+
+    let response = client.get("/crashy?crash=true").dispatch();
+    assert_eq!(response.status(), Status::InternalServerError);
+
+    let problem: Value = response.into_json().unwrap();
+    assert_eq!(problem["type"], "urn:py-apify:error:worker-failure");
+
+    assert_ne!(worker_pid(client), pid);
+}
+
+fn main() {
+    std::env::set_var("ROCKET_PY_APIFY", "{process_workers={crashy=1}}");
+
+    let client = Client::tracked(rocket()).expect("failed to build rocket");
+
+    test_prints_stay_out_of_the_channel(&client);
+    test_crashed_worker_is_restarted(&client);
+}
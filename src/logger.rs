@@ -1,8 +1,28 @@
-use env_logger;
-use log::LevelFilter;
+use env_logger::{Builder, Env};
+use std::io::Write;
 
-use env_logger::Builder;
+/// Environment variable selecting the output format: `human` (default) or
+/// `json`, one JSON object per line.
+const LOG_FORMAT_ENV: &str = "LOG_FORMAT";
 
+/// Sets up the logger. The level and the per-module filters are read from
+/// `RUST_LOG` (e.g. `RUST_LOG=warn,py_apify::request=info,camembert-masked-lm=debug`),
+/// `info` by default.
 pub fn setup_logger() {
-    Builder::new().filter(None, LevelFilter::Info).init();
+    let mut builder = Builder::from_env(Env::default().default_filter_or("info"));
+
+    if std::env::var(LOG_FORMAT_ENV).is_ok_and(|format| format.eq_ignore_ascii_case("json")) {
+        builder.format(|buf, record| {
+            let line = rocket::serde::json::json!({
+                "timestamp": buf.timestamp_millis().to_string(),
+                "level": record.level().as_str(),
+                "target": record.target(),
+                "message": record.args().to_string(),
+            });
+
+            writeln!(buf, "{}", line)
+        });
+    }
+
+    builder.init();
 }